@@ -0,0 +1,290 @@
+//! Timings for `build`, `get` and `modify` across sizes, layouts and access
+//! patterns.
+//!
+//! Run with `cargo bench --bench segment_tree`. Configuration comes from the
+//! environment:
+//!
+//! - `BENCH_SIZES`: comma separated element counts, `1000,10000,100000,1000000`
+//!   by default. Sizes up to `100000000` work but need several gigabytes of memory.
+//! - `BENCH_OPERATIONS`: queries or updates timed per pattern, `100000` by default.
+//! - `BENCH_FORMAT`: `csv` (default) or `json`.
+
+use segment_tree::{
+    CompactLayout, HeapLayout, MaxComputation, MaxSliceSumComputation, PointUpdate, SegmentTree,
+    SegmentTreeComputation, SumComputation, VebLayout, WideSegmentTree,
+};
+use serde_json::json;
+use std::{
+    env,
+    hint::black_box,
+    time::{Duration, Instant},
+};
+
+const DEFAULT_SIZES: [usize; 4] = [1_000, 10_000, 100_000, 1_000_000];
+const DEFAULT_OPERATIONS: usize = 100_000;
+const NARROW_WIDTH: usize = 16;
+
+struct Rng(u64);
+
+impl Rng {
+    fn next(&mut self) -> u64 {
+        self.0 ^= self.0 << 13;
+        self.0 ^= self.0 >> 7;
+        self.0 ^= self.0 << 17;
+        self.0
+    }
+
+    fn below(&mut self, bound: usize) -> usize {
+        (self.next() % bound as u64) as usize
+    }
+}
+
+#[derive(Clone, Copy)]
+enum Pattern {
+    Random,
+    Sequential,
+    Narrow,
+    Wide,
+}
+
+impl Pattern {
+    const ALL: [Pattern; 4] = [Self::Random, Self::Sequential, Self::Narrow, Self::Wide];
+
+    fn name(&self) -> &'static str {
+        match self {
+            Self::Random => "random",
+            Self::Sequential => "sequential",
+            Self::Narrow => "narrow",
+            Self::Wide => "wide",
+        }
+    }
+
+    fn range(&self, rng: &mut Rng, step: usize, len: usize) -> (usize, usize) {
+        match self {
+            Self::Random => {
+                let (a, b) = (rng.below(len), rng.below(len));
+                (a.min(b), a.max(b))
+            }
+            Self::Sequential => {
+                let left = step % len;
+                (left, (left + NARROW_WIDTH - 1).min(len - 1))
+            }
+            Self::Narrow => {
+                let left = rng.below(len);
+                (left, (left + rng.below(NARROW_WIDTH)).min(len - 1))
+            }
+            Self::Wide => {
+                let left = rng.below(len.div_ceil(4));
+                (left, len - 1 - rng.below(len.div_ceil(4)))
+            }
+        }
+    }
+}
+
+struct Record {
+    structure: &'static str,
+    layout: &'static str,
+    operation: &'static str,
+    pattern: &'static str,
+    size: usize,
+    iterations: usize,
+    elapsed: Duration,
+}
+
+impl Record {
+    fn ns_per_op(&self) -> f64 {
+        self.elapsed.as_nanos() as f64 / self.iterations as f64
+    }
+}
+
+fn bench_tree<T, B>(
+    structure: &'static str,
+    layout_name: &'static str,
+    build: B,
+    size: usize,
+    operations: usize,
+    records: &mut Vec<Record>,
+) where
+    T: PointUpdate<Input = i64>,
+    B: Fn(&[i64]) -> T,
+{
+    let mut rng = Rng(0x2545_f491_4f6c_dd1d ^ size as u64);
+    let values: Vec<i64> = (0..size)
+        .map(|_| rng.below(2_000_001) as i64 - 1_000_000)
+        .collect();
+
+    let repeats = (10_000_000 / size).clamp(1, 100);
+    let start = Instant::now();
+    for _ in 1..repeats {
+        black_box(build(black_box(&values)));
+    }
+    let mut tree = build(black_box(&values));
+    records.push(Record {
+        structure,
+        layout: layout_name,
+        operation: "build",
+        pattern: "full",
+        size,
+        iterations: repeats,
+        elapsed: start.elapsed(),
+    });
+
+    for pattern in Pattern::ALL {
+        let ranges: Vec<(usize, usize)> = (0..operations)
+            .map(|step| pattern.range(&mut rng, step, size))
+            .collect();
+
+        let start = Instant::now();
+        for &(left, right) in &ranges {
+            black_box(tree.get(black_box(left), black_box(right)).unwrap());
+        }
+        records.push(Record {
+            structure,
+            layout: layout_name,
+            operation: "get",
+            pattern: pattern.name(),
+            size,
+            iterations: operations,
+            elapsed: start.elapsed(),
+        });
+    }
+
+    for pattern in [Pattern::Random, Pattern::Sequential] {
+        let updates: Vec<(usize, i64)> = (0..operations)
+            .map(|step| (pattern.range(&mut rng, step, size).0, step as i64))
+            .collect();
+
+        let start = Instant::now();
+        for (pos, value) in &updates {
+            tree.modify(black_box(*pos), black_box(value)).unwrap();
+        }
+        records.push(Record {
+            structure,
+            layout: layout_name,
+            operation: "modify",
+            pattern: pattern.name(),
+            size,
+            iterations: operations,
+            elapsed: start.elapsed(),
+        });
+    }
+}
+
+fn bench_layouts<O, C>(
+    structure: &'static str,
+    size: usize,
+    operations: usize,
+    records: &mut Vec<Record>,
+) where
+    O: Default + Copy + PartialEq,
+    C: SegmentTreeComputation<Input = i64, Output = O>,
+{
+    bench_tree(
+        structure,
+        "heap",
+        |values| SegmentTree::<i64, O, C, _>::build_in(values, HeapLayout),
+        size,
+        operations,
+        records,
+    );
+    bench_tree(
+        structure,
+        "compact",
+        |values| SegmentTree::<i64, O, C, _>::build_in(values, CompactLayout),
+        size,
+        operations,
+        records,
+    );
+    bench_tree(
+        structure,
+        "veb",
+        |values| SegmentTree::<i64, O, C, _>::build_in(values, VebLayout),
+        size,
+        operations,
+        records,
+    );
+    bench_tree(
+        structure,
+        "wide-16",
+        WideSegmentTree::<i64, O, C, 16>::build,
+        size,
+        operations,
+        records,
+    );
+}
+
+fn parse_env<T: std::str::FromStr>(name: &str) -> Option<Vec<T>> {
+    let value = env::var(name).ok()?;
+    let parsed = value
+        .split(',')
+        .map(|part| part.trim().replace('_', "").parse())
+        .collect::<Result<Vec<T>, _>>();
+
+    match parsed {
+        Ok(parsed) => Some(parsed),
+        Err(_) => panic!("{name} should be a comma separated list of numbers, found <{value}>"),
+    }
+}
+
+fn main() {
+    // `cargo bench` passes `--bench`, and `cargo test --benches` runs us without it.
+    if !env::args().any(|arg| arg == "--bench") {
+        return;
+    }
+
+    let sizes = parse_env("BENCH_SIZES").unwrap_or(DEFAULT_SIZES.to_vec());
+    let operations = parse_env("BENCH_OPERATIONS")
+        .and_then(|operations| operations.first().copied())
+        .unwrap_or(DEFAULT_OPERATIONS);
+    let json = match env::var("BENCH_FORMAT").as_deref() {
+        Ok("json") => true,
+        Ok("csv") | Err(_) => false,
+        Ok(other) => panic!("BENCH_FORMAT should be csv or json, found <{other}>"),
+    };
+
+    let mut records = vec![];
+    for size in sizes.into_iter().filter(|&size| size > 0) {
+        bench_layouts::<i64, SumComputation<i64>>("sum", size, operations, &mut records);
+        bench_layouts::<i64, MaxComputation<i64>>("max", size, operations, &mut records);
+        bench_layouts::<_, MaxSliceSumComputation<i64>>(
+            "max-slice-sum",
+            size,
+            operations,
+            &mut records,
+        );
+    }
+
+    if json {
+        let records: Vec<_> = records
+            .iter()
+            .map(|record| {
+                json!({
+                    "structure": record.structure,
+                    "layout": record.layout,
+                    "operation": record.operation,
+                    "pattern": record.pattern,
+                    "size": record.size,
+                    "iterations": record.iterations,
+                    "total_ns": record.elapsed.as_nanos() as u64,
+                    "ns_per_op": record.ns_per_op(),
+                })
+            })
+            .collect();
+        println!("{}", serde_json::to_string_pretty(&records).unwrap());
+    } else {
+        println!("structure,layout,operation,pattern,size,iterations,total_ns,ns_per_op");
+        for record in &records {
+            println!(
+                "{},{},{},{},{},{},{},{:.2}",
+                record.structure,
+                record.layout,
+                record.operation,
+                record.pattern,
+                record.size,
+                record.iterations,
+                record.elapsed.as_nanos(),
+                record.ns_per_op()
+            );
+        }
+    }
+}
@@ -0,0 +1,38 @@
+#![no_main]
+
+use libfuzzer_sys::fuzz_target;
+use std::io;
+
+#[allow(dead_code)]
+#[path = "../../src/cli/mod.rs"]
+mod cli;
+
+use cli::{
+    json::run_json,
+    repl::run_repl,
+    script::{run_classic, run_commands},
+    tree::{Computation, ElementType},
+};
+
+// Errors are expected for malformed input, only panics are failures. The first
+// byte picks the mode, computation and element type, see `seeds/cli_input` for
+// inputs to start from, e.g. `cargo fuzz run cli_input corpus/cli_input seeds/cli_input`.
+fuzz_target!(|data: &[u8]| {
+    let Some((&selector, input)) = data.split_first() else {
+        return;
+    };
+    let computation: Computation = Computation::NAMES[usize::from(selector >> 2) % 8]
+        .parse()
+        .unwrap();
+    let element_type: ElementType = ElementType::NAMES[usize::from(selector >> 5) % 6]
+        .parse()
+        .unwrap();
+    let mut output = io::sink();
+
+    let _ = match selector % 4 {
+        0 => run_classic(input, &mut output, computation, element_type),
+        1 => run_commands(input, &mut output, computation, element_type),
+        2 => run_json(input, &mut output, computation, element_type),
+        _ => run_repl(input, &mut output, computation, element_type, false),
+    };
+});
@@ -0,0 +1,61 @@
+#![no_main]
+
+use libfuzzer_sys::fuzz_target;
+use segment_tree::{
+    testing::{generate_case, run_case, ByteSource, Config, Source},
+    BitAndComputation, BitOrComputation, BracketComputation, GcdComputation, MaxComputation,
+    MaxSliceSumComputation, MinComputation, ModularProductComputation, RollingHashComputation,
+    SegmentTree, SegmentTreeComputation, SumComputation, XorComputation,
+};
+use std::fmt::Debug;
+
+const CONFIG: Config = Config {
+    cases: 1,
+    max_len: 64,
+    max_operations: 64,
+    seed: 0,
+};
+
+const MODULUS: u64 = 1_000_000_007;
+
+// Small enough that sums over `CONFIG.max_len` elements can't overflow.
+fn small(source: &mut ByteSource) -> i64 {
+    source.range_i64(-(1 << 31), 1 << 31)
+}
+
+fn fuzz<C, G>(source: &mut ByteSource, mut generate: G)
+where
+    C: SegmentTreeComputation,
+    C::Input: Clone + Debug,
+    C::Output: Default + Copy + PartialEq + Debug,
+    G: FnMut(&mut ByteSource) -> C::Input,
+{
+    let case = generate_case(source, &CONFIG, &mut generate);
+    let build = SegmentTree::<C::Input, C::Output, C>::build;
+
+    if let Err((step, message)) = run_case::<C, _, _>(&case, &build) {
+        panic!("Step {step}: {message}\n{case:?}");
+    }
+}
+
+fuzz_target!(|data: &[u8]| {
+    let Some((&selector, data)) = data.split_first() else {
+        return;
+    };
+    let mut source = ByteSource::new(data);
+    let source = &mut source;
+
+    match selector % 11 {
+        0 => fuzz::<SumComputation<i64>, _>(source, small),
+        1 => fuzz::<MaxComputation<i64>, _>(source, small),
+        2 => fuzz::<MinComputation<i64>, _>(source, small),
+        3 => fuzz::<MaxSliceSumComputation<i64>, _>(source, small),
+        4 => fuzz::<XorComputation<u64>, _>(source, |source| source.next_u64()),
+        5 => fuzz::<GcdComputation<u64>, _>(source, |source| source.next_u64()),
+        6 => fuzz::<BitAndComputation<u64>, _>(source, |source| source.next_u64()),
+        7 => fuzz::<BitOrComputation<u64>, _>(source, |source| source.next_u64()),
+        8 => fuzz::<ModularProductComputation<MODULUS>, _>(source, |source| source.next_u64()),
+        9 => fuzz::<RollingHashComputation<u8>, _>(source, |source| source.below(256) as u8),
+        _ => fuzz::<BracketComputation, _>(source, |source| ['(', ')', 'x'][source.below(3)]),
+    }
+});
@@ -0,0 +1,230 @@
+use crate::{
+    computation::{append, prepend, MaxComputation, SegmentTreeComputation, SumComputation},
+    errors::{SegmentTreeError, SegmentTreeResult},
+};
+use core::marker::PhantomData;
+
+/// Segment tree over exactly `N` elements with every node stored inline, meant
+/// for small hot arrays that live on the stack or in a `static`.
+///
+/// Nodes use the iterative bottom-up numbering: leaf `pos` is node `N + pos`
+/// and node `index < N` combines nodes `2 * index` and `2 * index + 1`. Leaves
+/// and inner nodes are kept in two `[C::Output; N]` arrays.
+pub struct ArraySegmentTree<C, const N: usize>
+where
+    C: SegmentTreeComputation,
+{
+    leaves: [C::Output; N],
+    inner: [C::Output; N],
+    phantom: PhantomData<C>,
+}
+
+pub type SumArraySegmentTree<T, const N: usize> = ArraySegmentTree<SumComputation<T>, N>;
+pub type MaxArraySegmentTree<T, const N: usize> = ArraySegmentTree<MaxComputation<T>, N>;
+
+impl<C, const N: usize> ArraySegmentTree<C, N>
+where
+    C: SegmentTreeComputation,
+    C::Output: Copy,
+{
+    /// Tree where every element is `value`, usable in `const` items.
+    ///
+    /// `combine(value, value)` has to equal `value`, which holds for any value
+    /// of an idempotent computation and for the identity, e.g. `0` for sums.
+    pub const fn filled(value: C::Output) -> Self {
+        Self {
+            leaves: [value; N],
+            inner: [value; N],
+            phantom: PhantomData,
+        }
+    }
+
+    pub fn build(arr: &[C::Input; N]) -> Self
+    where
+        C::Output: Default,
+    {
+        Self::from_leaves(core::array::from_fn(|pos| C::init(&arr[pos])))
+    }
+
+    pub(crate) fn from_leaves(leaves: [C::Output; N]) -> Self
+    where
+        C::Output: Default,
+    {
+        let mut tree = Self {
+            leaves,
+            inner: [C::Output::default(); N],
+            phantom: PhantomData,
+        };
+
+        for index in (1..N).rev() {
+            tree.inner[index] = tree.combine_children(index);
+        }
+
+        tree
+    }
+
+    pub fn get(&self, left: usize, right: usize) -> SegmentTreeResult<C::Output> {
+        if left > right {
+            return Err(SegmentTreeError::InvalidRange { left, right });
+        } else if right >= N {
+            return Err(SegmentTreeError::OutOfBounds {
+                index: right,
+                len: N,
+            });
+        }
+
+        let mut left_result = None;
+        let mut right_result = None;
+        let (mut left, mut right) = (left + N, right + N + 1);
+
+        while left < right {
+            if left % 2 == 1 {
+                left_result = Some(append::<C>(left_result, self.node(left)));
+                left += 1;
+            }
+            if right % 2 == 1 {
+                right -= 1;
+                right_result = Some(prepend::<C>(self.node(right), right_result));
+            }
+
+            left /= 2;
+            right /= 2;
+        }
+
+        match (left_result, right_result) {
+            (Some(left_result), Some(right_result)) => Ok(C::combine(&left_result, &right_result)),
+            (result, None) | (None, result) => {
+                Ok(result.expect("A non-empty range covers at least one node"))
+            }
+        }
+    }
+
+    pub fn modify(&mut self, pos: usize, value: &C::Input) -> SegmentTreeResult<()> {
+        if pos >= N {
+            return Err(SegmentTreeError::OutOfBounds { index: pos, len: N });
+        }
+
+        self.leaves[pos] = C::update(&self.leaves[pos], value);
+
+        let mut index = (pos + N) / 2;
+        while index > 0 {
+            self.inner[index] = self.combine_children(index);
+            index /= 2;
+        }
+
+        Ok(())
+    }
+
+    pub const fn len(&self) -> usize {
+        N
+    }
+
+    pub const fn is_empty(&self) -> bool {
+        N == 0
+    }
+
+    fn node(&self, index: usize) -> C::Output {
+        if index >= N {
+            self.leaves[index - N]
+        } else {
+            self.inner[index]
+        }
+    }
+
+    fn combine_children(&self, index: usize) -> C::Output {
+        C::combine(&self.node(index * 2), &self.node(index * 2 + 1))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{ArraySegmentTree, MaxArraySegmentTree, SumArraySegmentTree};
+    use crate::{
+        computation::{RollingHash, RollingHashComputation},
+        SegmentTreeError,
+    };
+    use std::mem::size_of;
+
+    const ZEROS: SumArraySegmentTree<i64, 6> = SumArraySegmentTree::filled(0);
+
+    #[test]
+    fn test_get_and_modify() {
+        let mut arr = [4i64, -7, 12, 0, 3, 3, -1, 25, 8, -9, 6];
+        let mut sum = SumArraySegmentTree::build(&arr);
+        let mut max = MaxArraySegmentTree::build(&arr);
+
+        for (pos, value) in [(0, 10), (10, -3), (5, 40), (7, 0)] {
+            arr[pos] = value;
+            assert_eq!(sum.modify(pos, &value), Ok(()));
+            assert_eq!(max.modify(pos, &value), Ok(()));
+
+            for left in 0..arr.len() {
+                for right in left..arr.len() {
+                    let slice = &arr[left..=right];
+
+                    assert_eq!(sum.get(left, right), Ok(slice.iter().sum()));
+                    assert_eq!(max.get(left, right), Ok(*slice.iter().max().unwrap()));
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_non_commutative_order() {
+        let text = *b"the quick brown fox";
+        let tree = ArraySegmentTree::<RollingHashComputation<u8>, 19>::build(&text);
+
+        for left in 0..text.len() {
+            for right in left..text.len() {
+                let expected = RollingHash::from_slice(&text[left..=right]);
+
+                assert_eq!(tree.get(left, right), Ok(expected));
+            }
+        }
+    }
+
+    #[test]
+    fn test_filled() {
+        let mut tree = ZEROS;
+        assert_eq!(tree.get(0, 5), Ok(0));
+
+        assert_eq!(tree.modify(2, &5), Ok(()));
+        assert_eq!(tree.modify(5, &-1), Ok(()));
+        assert_eq!(tree.get(0, 5), Ok(4));
+        assert_eq!(tree.get(3, 5), Ok(-1));
+        assert_eq!(ZEROS.get(0, 5), Ok(0));
+
+        let max = MaxArraySegmentTree::<u8, 3>::filled(7);
+        assert_eq!(max.get(1, 2), Ok(7));
+    }
+
+    #[test]
+    fn test_errors() {
+        let mut tree = SumArraySegmentTree::build(&[1, 2, 3]);
+        let (left, right) = (2, 1);
+
+        assert_eq!(
+            size_of::<SumArraySegmentTree<i32, 3>>(),
+            2 * 3 * size_of::<i32>()
+        );
+        assert_eq!(
+            tree.get(left, right),
+            Err(SegmentTreeError::InvalidRange { left, right })
+        );
+        assert_eq!(
+            tree.get(0, 3),
+            Err(SegmentTreeError::OutOfBounds { index: 3, len: 3 })
+        );
+        assert_eq!(
+            tree.modify(3, &1),
+            Err(SegmentTreeError::OutOfBounds { index: 3, len: 3 })
+        );
+
+        let empty = SumArraySegmentTree::<i32, 0>::build(&[]);
+        assert!(empty.is_empty());
+        assert_eq!(
+            empty.get(0, 0),
+            Err(SegmentTreeError::OutOfBounds { index: 0, len: 0 })
+        );
+    }
+}
@@ -0,0 +1,400 @@
+use crate::{
+    errors::{SegmentTreeError, SegmentTreeResult},
+    segment_tree::{
+        traversal::{self, Builder, Nodes},
+        NoopTracer,
+    },
+};
+use alloc::{vec, vec::Vec};
+use core::sync::atomic::Ordering::SeqCst;
+
+#[cfg(target_has_atomic = "ptr")]
+use core::sync::atomic::AtomicUsize;
+#[cfg(target_has_atomic = "32")]
+use core::sync::atomic::{AtomicI32, AtomicU32};
+#[cfg(target_has_atomic = "64")]
+use core::sync::atomic::{AtomicI64, AtomicU64};
+
+pub trait AtomicInteger: Send + Sync {
+    type Value: Copy + Ord;
+
+    const ZERO: Self::Value;
+
+    fn new(value: Self::Value) -> Self;
+
+    fn load(&self) -> Self::Value;
+
+    fn fetch_add(&self, delta: Self::Value) -> Self::Value;
+
+    fn fetch_max(&self, value: Self::Value) -> Self::Value;
+
+    fn wrapping_add(left: Self::Value, right: Self::Value) -> Self::Value;
+}
+
+macro_rules! impl_atomic_integer {
+    ($($atomic:ty => $value:ty),*) => {
+        $(
+            impl AtomicInteger for $atomic {
+                type Value = $value;
+
+                const ZERO: $value = 0;
+
+                fn new(value: $value) -> Self {
+                    <$atomic>::new(value)
+                }
+
+                fn load(&self) -> $value {
+                    <$atomic>::load(self, SeqCst)
+                }
+
+                fn fetch_add(&self, delta: $value) -> $value {
+                    <$atomic>::fetch_add(self, delta, SeqCst)
+                }
+
+                fn fetch_max(&self, value: $value) -> $value {
+                    <$atomic>::fetch_max(self, value, SeqCst)
+                }
+
+                fn wrapping_add(left: $value, right: $value) -> $value {
+                    left.wrapping_add(right)
+                }
+            }
+        )*
+    };
+}
+
+// Each width is only implemented where the target has atomic read-modify-write
+// operations of that size: 32-bit microcontrollers often stop at the pointer
+// width and some, like `thumbv6m`, only have atomic loads and stores.
+#[cfg(target_has_atomic = "32")]
+impl_atomic_integer!(AtomicI32 => i32, AtomicU32 => u32);
+#[cfg(target_has_atomic = "64")]
+impl_atomic_integer!(AtomicI64 => i64, AtomicU64 => u64);
+#[cfg(target_has_atomic = "ptr")]
+impl_atomic_integer!(AtomicUsize => usize);
+
+/// Heap-indexed nodes shared by the atomic trees. Updates touch every node on
+/// the path from the root to the leaf with a single atomic operation each.
+struct AtomicNodes<A>
+where
+    A: AtomicInteger,
+{
+    data: Vec<A>,
+    len: usize,
+    combine: fn(A::Value, A::Value) -> A::Value,
+}
+
+/// Plain node values of an atomic tree before they are shared.
+struct ValueBuilder<'a, A>
+where
+    A: AtomicInteger,
+{
+    input: &'a [A::Value],
+    values: Vec<A::Value>,
+    combine: fn(A::Value, A::Value) -> A::Value,
+}
+
+impl<A> AtomicNodes<A>
+where
+    A: AtomicInteger,
+{
+    fn build(arr: &[A::Value], combine: fn(A::Value, A::Value) -> A::Value) -> Self {
+        let len = arr.len();
+        let mut builder = ValueBuilder::<A> {
+            input: arr,
+            values: vec![A::ZERO; len * 4],
+            combine,
+        };
+
+        if len > 0 {
+            traversal::build(&mut builder, 1, 0, len - 1, &mut NoopTracer);
+        }
+
+        Self {
+            data: builder.values.into_iter().map(A::new).collect(),
+            len,
+            combine,
+        }
+    }
+
+    fn check_pos(&self, pos: usize) -> SegmentTreeResult<()> {
+        if pos >= self.len {
+            Err(SegmentTreeError::OutOfBounds {
+                index: pos,
+                len: self.len,
+            })
+        } else {
+            Ok(())
+        }
+    }
+
+    fn update_path(&self, pos: usize, update: impl Fn(&A)) -> SegmentTreeResult<()> {
+        self.check_pos(pos)?;
+
+        let (mut index, mut cur_left, mut cur_right) = (1, 0, self.len - 1);
+        loop {
+            update(&self.data[index]);
+            if cur_left == cur_right {
+                return Ok(());
+            }
+
+            let mid = (cur_left + cur_right) / 2;
+            if pos <= mid {
+                (index, cur_right) = (index * 2, mid);
+            } else {
+                (index, cur_left) = (index * 2 + 1, mid + 1);
+            }
+        }
+    }
+
+    fn fold(&self, left: usize, right: usize) -> SegmentTreeResult<A::Value> {
+        if left > right {
+            Err(SegmentTreeError::InvalidRange { left, right })
+        } else if right >= self.len {
+            Err(SegmentTreeError::OutOfBounds {
+                index: right,
+                len: self.len,
+            })
+        } else {
+            let mut tracer = NoopTracer;
+            Ok(traversal::fold(
+                self,
+                1,
+                0,
+                self.len - 1,
+                left,
+                right,
+                &mut tracer,
+            ))
+        }
+    }
+}
+
+impl<A> Nodes for AtomicNodes<A>
+where
+    A: AtomicInteger,
+{
+    type Output = A::Value;
+
+    fn node(&self, index: usize, _: usize, _: usize) -> A::Value {
+        self.data[index].load()
+    }
+
+    fn combine(&self, left: &A::Value, right: &A::Value) -> A::Value {
+        (self.combine)(*left, *right)
+    }
+}
+
+impl<A> Builder for ValueBuilder<'_, A>
+where
+    A: AtomicInteger,
+{
+    type Output = A::Value;
+
+    fn leaf(&mut self, pos: usize) -> A::Value {
+        self.input[pos]
+    }
+
+    fn combine(&mut self, left: &A::Value, right: &A::Value) -> A::Value {
+        (self.combine)(*left, *right)
+    }
+
+    fn store(&mut self, index: usize, _: usize, _: usize, value: &A::Value) {
+        self.values[index] = *value;
+    }
+}
+
+/// Lock-free sum tree for integer counters. `add` is lock-free and `get` is
+/// wait-free. Each node is updated atomically, but an `add` reaches the nodes
+/// on its path one at a time, so a concurrent `get` may combine nodes that
+/// already include it with nodes that don't yet. Once every `add` has
+/// returned, `get` sees all of them. Sums wrap around on overflow like the
+/// underlying atomics.
+pub struct AtomicSumSegmentTree<A>
+where
+    A: AtomicInteger,
+{
+    nodes: AtomicNodes<A>,
+}
+
+impl<A> AtomicSumSegmentTree<A>
+where
+    A: AtomicInteger,
+{
+    pub fn build(arr: &[A::Value]) -> Self {
+        Self {
+            nodes: AtomicNodes::build(arr, A::wrapping_add),
+        }
+    }
+
+    pub fn get(&self, left: usize, right: usize) -> SegmentTreeResult<A::Value> {
+        self.nodes.fold(left, right)
+    }
+
+    pub fn add(&self, pos: usize, delta: A::Value) -> SegmentTreeResult<()> {
+        self.nodes.update_path(pos, |node| {
+            node.fetch_add(delta);
+        })
+    }
+
+    pub fn len(&self) -> usize {
+        self.nodes.len
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.nodes.len == 0
+    }
+}
+
+/// Lock-free maximum tree for integer gauges. Values only grow through
+/// `fetch_max`, and `get` is wait-free. As with [`AtomicSumSegmentTree`], each
+/// node is updated atomically but a concurrent `get` may see a `fetch_max` in
+/// some nodes on its path and not in others.
+pub struct AtomicMaxSegmentTree<A>
+where
+    A: AtomicInteger,
+{
+    nodes: AtomicNodes<A>,
+}
+
+impl<A> AtomicMaxSegmentTree<A>
+where
+    A: AtomicInteger,
+{
+    pub fn build(arr: &[A::Value]) -> Self {
+        Self {
+            nodes: AtomicNodes::build(arr, Ord::max),
+        }
+    }
+
+    pub fn get(&self, left: usize, right: usize) -> SegmentTreeResult<A::Value> {
+        self.nodes.fold(left, right)
+    }
+
+    pub fn fetch_max(&self, pos: usize, value: A::Value) -> SegmentTreeResult<()> {
+        self.nodes.update_path(pos, |node| {
+            node.fetch_max(value);
+        })
+    }
+
+    pub fn len(&self) -> usize {
+        self.nodes.len
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.nodes.len == 0
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{AtomicMaxSegmentTree, AtomicSumSegmentTree};
+    use crate::SegmentTreeError;
+    use std::{
+        sync::{
+            atomic::{AtomicI64, AtomicU64},
+            Arc,
+        },
+        thread,
+    };
+
+    #[test]
+    fn test_sum() {
+        let mut arr = [1i64, -3, 7, 27, 73];
+        let tree = AtomicSumSegmentTree::<AtomicI64>::build(&arr);
+
+        assert!(!tree.is_empty());
+        assert_eq!(tree.len(), arr.len());
+
+        for (pos, delta) in [(0, 5), (3, -30), (4, 1)] {
+            arr[pos] += delta;
+            assert_eq!(tree.add(pos, delta), Ok(()));
+
+            for left in 0..arr.len() {
+                for right in left..arr.len() {
+                    assert_eq!(tree.get(left, right), Ok(arr[left..=right].iter().sum()));
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_max() {
+        let mut arr = [1u64, 3, 7, 27, 73, 0];
+        let tree = AtomicMaxSegmentTree::<AtomicU64>::build(&arr);
+
+        for (pos, value) in [(0, 5), (3, 2), (5, 100), (1, 8)] {
+            arr[pos] = arr[pos].max(value);
+            assert_eq!(tree.fetch_max(pos, value), Ok(()));
+
+            for left in 0..arr.len() {
+                for right in left..arr.len() {
+                    let expected = *arr[left..=right].iter().max().unwrap();
+
+                    assert_eq!(tree.get(left, right), Ok(expected));
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_errors() {
+        let tree = AtomicSumSegmentTree::<AtomicU64>::build(&[1, 2, 3]);
+
+        assert_eq!(
+            tree.get(2, 1),
+            Err(SegmentTreeError::InvalidRange { left: 2, right: 1 })
+        );
+        assert_eq!(
+            tree.get(1, 3),
+            Err(SegmentTreeError::OutOfBounds { index: 3, len: 3 })
+        );
+        assert_eq!(
+            tree.add(3, 1),
+            Err(SegmentTreeError::OutOfBounds { index: 3, len: 3 })
+        );
+
+        let empty = AtomicMaxSegmentTree::<AtomicI64>::build(&[]);
+
+        assert!(empty.is_empty());
+        assert_eq!(
+            empty.fetch_max(0, 1),
+            Err(SegmentTreeError::OutOfBounds { index: 0, len: 0 })
+        );
+    }
+
+    #[test]
+    fn test_concurrent_counters() {
+        const LEN: usize = 100;
+        const THREADS: u64 = 8;
+        const ADDS: u64 = 1000;
+
+        let sums = Arc::new(AtomicSumSegmentTree::<AtomicU64>::build(&[0; LEN]));
+        let maxima = Arc::new(AtomicMaxSegmentTree::<AtomicU64>::build(&[0; LEN]));
+
+        let handles: Vec<_> = (0..THREADS)
+            .map(|thread| {
+                let (sums, maxima) = (Arc::clone(&sums), Arc::clone(&maxima));
+                thread::spawn(move || {
+                    let mut prev_total = 0;
+                    for step in 0..ADDS {
+                        let pos = ((thread * ADDS + step) * 31) as usize % LEN;
+                        sums.add(pos, 1).unwrap();
+                        maxima.fetch_max(pos, thread * ADDS + step).unwrap();
+
+                        let total = sums.get(0, LEN - 1).unwrap();
+                        assert!(total > prev_total);
+                        prev_total = total;
+                    }
+                })
+            })
+            .collect();
+
+        for handle in handles {
+            handle.join().unwrap();
+        }
+
+        assert_eq!(sums.get(0, LEN - 1), Ok(THREADS * ADDS));
+        assert_eq!(maxima.get(0, LEN - 1), Ok(THREADS * ADDS - 1));
+    }
+}
@@ -0,0 +1,209 @@
+use alloc::vec::Vec;
+use core::ops::RangeInclusive;
+
+use crate::{
+    computation::BracketBalance, errors::SegmentTreeResult, segment_tree::BracketSegmentTree,
+};
+
+pub struct BracketSequence {
+    tree: BracketSegmentTree,
+}
+
+impl BracketSequence {
+    pub fn build(arr: &[char]) -> Self {
+        Self {
+            tree: BracketSegmentTree::build(arr),
+        }
+    }
+
+    pub fn balance(&self, range: RangeInclusive<usize>) -> SegmentTreeResult<BracketBalance> {
+        self.tree.get(*range.start(), *range.end())
+    }
+
+    pub fn is_balanced(&self, range: RangeInclusive<usize>) -> SegmentTreeResult<bool> {
+        Ok(self.balance(range)?.is_balanced())
+    }
+
+    pub fn longest_correct_subsequence(
+        &self,
+        range: RangeInclusive<usize>,
+    ) -> SegmentTreeResult<usize> {
+        Ok(self.balance(range)?.matched_pairs() * 2)
+    }
+
+    pub fn find_match(&self, pos: usize) -> SegmentTreeResult<Option<usize>> {
+        let bracket = self.tree.get(pos, pos)?;
+
+        if bracket.unmatched_open() == 1 {
+            self.find_close_match(pos)
+        } else if bracket.unmatched_close() == 1 {
+            self.find_open_match(pos)
+        } else {
+            Ok(None)
+        }
+    }
+
+    pub fn modify(&mut self, pos: usize, value: &char) -> SegmentTreeResult<()> {
+        self.tree.modify(pos, value)
+    }
+
+    pub fn len(&self) -> usize {
+        self.tree.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.tree.is_empty()
+    }
+
+    fn find_close_match(&self, pos: usize) -> SegmentTreeResult<Option<usize>> {
+        if pos + 1 == self.len() || self.tree.get(pos + 1, self.len() - 1)?.unmatched_close() == 0 {
+            return Ok(None);
+        }
+
+        let (mut low, mut high) = (pos + 1, self.len() - 1);
+        while low < high {
+            let mid = (low + high) / 2;
+
+            if self.tree.get(pos + 1, mid)?.unmatched_close() > 0 {
+                high = mid;
+            } else {
+                low = mid + 1;
+            }
+        }
+
+        Ok(Some(low))
+    }
+
+    fn find_open_match(&self, pos: usize) -> SegmentTreeResult<Option<usize>> {
+        if pos == 0 || self.tree.get(0, pos - 1)?.unmatched_open() == 0 {
+            return Ok(None);
+        }
+
+        let (mut low, mut high) = (0, pos - 1);
+        while low < high {
+            let mid = (low + high).div_ceil(2);
+
+            if self.tree.get(mid, pos - 1)?.unmatched_open() > 0 {
+                low = mid;
+            } else {
+                high = mid - 1;
+            }
+        }
+
+        Ok(Some(low))
+    }
+}
+
+impl From<&str> for BracketSequence {
+    fn from(value: &str) -> Self {
+        let chars: Vec<char> = value.chars().collect();
+        Self::build(&chars)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::BracketSequence;
+    use crate::SegmentTreeError;
+
+    fn naive_match(arr: &[char], pos: usize) -> Option<usize> {
+        let mut stack = vec![];
+        let mut matches = vec![None; arr.len()];
+
+        for (index, value) in arr.iter().enumerate() {
+            match value {
+                '(' => stack.push(index),
+                ')' => {
+                    if let Some(open) = stack.pop() {
+                        matches[open] = Some(index);
+                        matches[index] = Some(open);
+                    }
+                }
+                _ => {}
+            }
+        }
+
+        matches[pos]
+    }
+
+    #[test]
+    fn test_is_balanced() {
+        let arr: Vec<char> = "(a)(()))((b)".chars().collect();
+        let sequence = BracketSequence::build(&arr);
+
+        assert_eq!(sequence.len(), arr.len());
+
+        for left in 0..arr.len() {
+            for right in left..arr.len() {
+                let mut depth = 0i64;
+                let mut expected = true;
+                for value in &arr[left..=right] {
+                    match value {
+                        '(' => depth += 1,
+                        ')' => depth -= 1,
+                        _ => {}
+                    }
+                    expected &= depth >= 0;
+                }
+                expected &= depth == 0;
+
+                assert_eq!(sequence.is_balanced(left..=right), Ok(expected));
+            }
+        }
+    }
+
+    #[test]
+    fn test_longest_correct_subsequence() {
+        let sequence = BracketSequence::from("())(())((");
+
+        assert_eq!(sequence.longest_correct_subsequence(0..=8), Ok(6));
+        assert_eq!(sequence.longest_correct_subsequence(0..=2), Ok(2));
+        assert_eq!(sequence.longest_correct_subsequence(1..=2), Ok(0));
+        assert_eq!(sequence.longest_correct_subsequence(2..=6), Ok(4));
+        assert_eq!(sequence.longest_correct_subsequence(7..=8), Ok(0));
+    }
+
+    #[test]
+    fn test_find_match() {
+        let arr: Vec<char> = ")(()x(())(()".chars().collect();
+        let sequence = BracketSequence::build(&arr);
+
+        for pos in 0..arr.len() {
+            assert_eq!(sequence.find_match(pos), Ok(naive_match(&arr, pos)));
+        }
+    }
+
+    #[test]
+    fn test_modify() {
+        let mut sequence = BracketSequence::from("(()");
+
+        assert_eq!(sequence.is_balanced(0..=2), Ok(false));
+        assert_eq!(sequence.find_match(0), Ok(None));
+
+        assert_eq!(sequence.modify(1, &')'), Ok(()));
+        assert_eq!(sequence.modify(2, &'x'), Ok(()));
+
+        assert_eq!(sequence.is_balanced(0..=2), Ok(true));
+        assert_eq!(sequence.find_match(0), Ok(Some(1)));
+        assert_eq!(sequence.find_match(1), Ok(Some(0)));
+        assert_eq!(sequence.find_match(2), Ok(None));
+    }
+
+    #[test]
+    fn test_errors() {
+        let mut sequence = BracketSequence::from("()");
+
+        assert_eq!(
+            sequence.is_balanced(0..=2),
+            Err(SegmentTreeError::OutOfBounds { index: 2, len: 2 })
+        );
+        assert_eq!(
+            sequence.find_match(2),
+            Err(SegmentTreeError::OutOfBounds { index: 2, len: 2 })
+        );
+        assert_eq!(
+            sequence.modify(5, &'('),
+            Err(SegmentTreeError::OutOfBounds { index: 5, len: 2 })
+        );
+    }
+}
@@ -0,0 +1,126 @@
+use std::{
+    fmt::{self, Display},
+    ops::Add,
+    str::FromStr,
+};
+
+pub trait CheckedAdd: Sized {
+    fn checked_add(self, other: Self) -> Option<Self>;
+}
+
+macro_rules! impl_checked_add {
+    ($($ty:ty),*) => {
+        $(
+            impl CheckedAdd for $ty {
+                fn checked_add(self, other: Self) -> Option<Self> {
+                    <$ty>::checked_add(self, other)
+                }
+            }
+        )*
+    };
+}
+
+impl_checked_add!(i32, i64, i128, u32, u64, u128);
+
+/// Element whose additions report overflow instead of panicking or wrapping,
+/// so the CLI can reuse the library computations on untrusted input.
+///
+/// An overflowed value remembers its direction and absorbs further additions:
+/// it compares below or above every value, so a `max` taken by a computation
+/// discards a candidate that fell below the range and reports one that went
+/// above it. Adding overflows in opposite directions can't tell which way the
+/// sum went, so it conservatively overflows upwards. A partial result that
+/// leaves the range is never brought back by later additions.
+#[derive(Debug, PartialEq, Eq, PartialOrd, Ord, Clone, Copy)]
+pub enum Checked<T> {
+    Underflow,
+    Value(T),
+    Overflow,
+}
+
+impl<T> Checked<T> {
+    pub fn get(&self) -> Option<&T> {
+        match self {
+            Self::Value(value) => Some(value),
+            Self::Underflow | Self::Overflow => None,
+        }
+    }
+}
+
+impl<T: CheckedAdd + Default + Ord> Add for Checked<T> {
+    type Output = Self;
+
+    fn add(self, other: Self) -> Self {
+        match (self, other) {
+            (Self::Value(left), Self::Value(right)) => {
+                let negative = right < T::default();
+
+                match left.checked_add(right) {
+                    Some(sum) => Self::Value(sum),
+                    None if negative => Self::Underflow,
+                    None => Self::Overflow,
+                }
+            }
+            (Self::Underflow, Self::Value(_))
+            | (Self::Value(_), Self::Underflow)
+            | (Self::Underflow, Self::Underflow) => Self::Underflow,
+            _ => Self::Overflow,
+        }
+    }
+}
+
+impl<T: Default> Default for Checked<T> {
+    fn default() -> Self {
+        Self::Value(T::default())
+    }
+}
+
+impl<T: FromStr> FromStr for Checked<T> {
+    type Err = T::Err;
+
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        value.parse().map(Self::Value)
+    }
+}
+
+impl<T: Display> Display for Checked<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Value(value) => value.fmt(f),
+            Self::Underflow | Self::Overflow => write!(f, "overflow"),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Checked;
+
+    #[test]
+    fn test_overflow_poisons() {
+        let max: Checked<i64> = i64::MAX.to_string().parse().unwrap();
+        let one: Checked<i64> = "1".parse().unwrap();
+        let overflow = max + one;
+
+        assert_eq!(overflow.get(), None);
+        assert_eq!((overflow + Checked::default()).get(), None);
+        assert_eq!(overflow.max(max), overflow);
+        assert_eq!(max.max(one), max);
+        assert_eq!((one + one).get(), Some(&2));
+    }
+
+    #[test]
+    fn test_underflow_loses_max() {
+        let min: Checked<i64> = i64::MIN.to_string().parse().unwrap();
+        let minus_one: Checked<i64> = "-1".parse().unwrap();
+        let max: Checked<i64> = i64::MAX.to_string().parse().unwrap();
+        let underflow = min + minus_one;
+
+        assert_eq!(underflow, Checked::Underflow);
+        assert_eq!((underflow + max).get(), None);
+        assert_eq!(underflow.max(minus_one), minus_one);
+        assert_eq!(underflow.max(min), min);
+        assert_eq!(underflow + (max + max), Checked::Overflow);
+        assert_eq!(underflow.to_string(), "overflow");
+    }
+}
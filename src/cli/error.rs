@@ -0,0 +1,42 @@
+use std::io;
+
+use segment_tree::SegmentTreeError;
+use thiserror::Error;
+
+#[derive(Error, Debug, PartialEq, Eq, Clone)]
+pub enum TreeError {
+    #[error("Value <{0}> can't be parsed as the element type")]
+    InvalidValue(String),
+    #[error("Result overflows the element type")]
+    Overflow,
+    #[error(transparent)]
+    SegmentTree(#[from] SegmentTreeError),
+}
+
+#[derive(Error, Debug)]
+pub enum CliError {
+    #[error("{0}")]
+    Usage(String),
+    #[error("line {line}: {message}")]
+    Parse { line: usize, message: String },
+    #[error("line {line}: {source}")]
+    Tree { line: usize, source: TreeError },
+    #[error(transparent)]
+    Io(#[from] io::Error),
+}
+
+impl CliError {
+    pub fn parse(line: usize, message: impl Into<String>) -> Self {
+        Self::Parse {
+            line,
+            message: message.into(),
+        }
+    }
+
+    pub fn tree(line: usize, source: impl Into<TreeError>) -> Self {
+        Self::Tree {
+            line,
+            source: source.into(),
+        }
+    }
+}
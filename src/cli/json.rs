@@ -0,0 +1,382 @@
+use std::io::{BufRead, Write};
+
+use segment_tree::SegmentTreeError;
+use serde::Deserialize;
+use serde_json::{json, Number, Value};
+
+use super::{
+    error::{CliError, TreeError},
+    tree::{build_tree, Computation, DynTree, ElementType},
+};
+
+#[derive(Deserialize)]
+struct Envelope {
+    #[serde(default)]
+    id: Value,
+    #[serde(flatten)]
+    request: Request,
+}
+
+#[derive(Deserialize)]
+#[serde(tag = "op", rename_all = "snake_case", deny_unknown_fields)]
+enum Request {
+    Build {
+        values: Vec<Scalar>,
+        computation: Option<String>,
+        #[serde(rename = "type")]
+        element_type: Option<String>,
+    },
+    Get {
+        left: usize,
+        right: usize,
+    },
+    Modify {
+        pos: usize,
+        value: Scalar,
+    },
+    Len,
+}
+
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum Scalar {
+    Number(Number),
+    String(String),
+}
+
+impl Scalar {
+    fn into_string(self) -> String {
+        match self {
+            Self::Number(number) => number.to_string(),
+            Self::String(string) => string,
+        }
+    }
+}
+
+struct ErrorResponse {
+    kind: &'static str,
+    message: String,
+    details: Value,
+}
+
+impl ErrorResponse {
+    fn new(kind: &'static str, message: impl Into<String>) -> Self {
+        Self {
+            kind,
+            message: message.into(),
+            details: json!({}),
+        }
+    }
+}
+
+impl From<TreeError> for ErrorResponse {
+    fn from(error: TreeError) -> Self {
+        let message = error.to_string();
+
+        match error {
+            TreeError::InvalidValue(value) => Self {
+                kind: "invalid_value",
+                message,
+                details: json!({ "value": value }),
+            },
+            TreeError::Overflow => Self {
+                kind: "overflow",
+                message,
+                details: json!({}),
+            },
+            TreeError::SegmentTree(SegmentTreeError::OutOfBounds { index, len }) => Self {
+                kind: "out_of_bounds",
+                message,
+                details: json!({ "index": index, "len": len }),
+            },
+            TreeError::SegmentTree(SegmentTreeError::InvalidRange { left, right }) => Self {
+                kind: "invalid_range",
+                message,
+                details: json!({ "left": left, "right": right }),
+            },
+            TreeError::SegmentTree(SegmentTreeError::InvalidCheckpoint) => Self {
+                kind: "invalid_checkpoint",
+                message,
+                details: json!({}),
+            },
+            TreeError::SegmentTree(SegmentTreeError::InvalidNode { index, left, right }) => Self {
+                kind: "invalid_node",
+                message,
+                details: json!({ "index": index, "left": left, "right": right }),
+            },
+            TreeError::SegmentTree(SegmentTreeError::CapacityExceeded { len, capacity }) => Self {
+                kind: "capacity_exceeded",
+                message,
+                details: json!({ "len": len, "capacity": capacity }),
+            },
+            TreeError::SegmentTree(SegmentTreeError::LengthMismatch { left, right }) => Self {
+                kind: "length_mismatch",
+                message,
+                details: json!({ "left": left, "right": right }),
+            },
+        }
+    }
+}
+
+pub struct Session {
+    computation: Computation,
+    element_type: ElementType,
+    tree: Option<Box<dyn DynTree>>,
+}
+
+impl Session {
+    pub fn new(computation: Computation, element_type: ElementType) -> Self {
+        Self {
+            computation,
+            element_type,
+            tree: None,
+        }
+    }
+
+    pub fn handle(&mut self, line: &str) -> Value {
+        let (id, result) = match serde_json::from_str::<Envelope>(line) {
+            Ok(Envelope { id, request }) => (id, self.execute(request)),
+            Err(error) => (
+                Value::Null,
+                Err(ErrorResponse::new("invalid_request", error.to_string())),
+            ),
+        };
+
+        match result {
+            Ok(result) => json!({ "id": id, "ok": true, "result": result }),
+            Err(error) => {
+                let mut details = error.details;
+                details["kind"] = json!(error.kind);
+                details["message"] = json!(error.message);
+
+                json!({ "id": id, "ok": false, "error": details })
+            }
+        }
+    }
+
+    fn execute(&mut self, request: Request) -> Result<Value, ErrorResponse> {
+        if let Request::Build {
+            values,
+            computation,
+            element_type,
+        } = request
+        {
+            let computation = match computation {
+                Some(name) => name
+                    .parse()
+                    .map_err(|e| ErrorResponse::new("invalid_request", e))?,
+                None => self.computation,
+            };
+            let element_type = match element_type {
+                Some(name) => name
+                    .parse()
+                    .map_err(|e| ErrorResponse::new("invalid_request", e))?,
+                None => self.element_type,
+            };
+            let values: Vec<String> = values.into_iter().map(Scalar::into_string).collect();
+            let values: Vec<&str> = values.iter().map(String::as_str).collect();
+            let tree = build_tree(computation, element_type, &values)?;
+            let len = tree.len();
+            self.tree = Some(tree);
+
+            return Ok(json!(len));
+        }
+
+        let tree = self
+            .tree
+            .as_mut()
+            .ok_or_else(|| ErrorResponse::new("no_tree", "No tree has been built yet"))?;
+
+        match request {
+            Request::Get { left, right } => Ok(to_json(tree.get(left, right)?)),
+            Request::Modify { pos, value } => {
+                tree.modify(pos, &value.into_string())?;
+                Ok(Value::Null)
+            }
+            Request::Len => Ok(json!(tree.len())),
+            Request::Build { .. } => unreachable!("Build requests are handled above"),
+        }
+    }
+}
+
+fn to_json(rendered: String) -> Value {
+    if let Ok(value) = rendered.parse::<i64>() {
+        json!(value)
+    } else if let Ok(value) = rendered.parse::<u64>() {
+        json!(value)
+    } else {
+        json!(rendered)
+    }
+}
+
+pub fn run_json(
+    input: impl BufRead,
+    output: &mut impl Write,
+    computation: Computation,
+    element_type: ElementType,
+) -> Result<(), CliError> {
+    let mut session = Session::new(computation, element_type);
+
+    for line in input.lines() {
+        let line = line?;
+        if line.trim().is_empty() {
+            continue;
+        }
+
+        writeln!(output, "{}", session.handle(&line))?;
+        output.flush()?;
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Session;
+    use crate::cli::tree::{Computation, ElementType};
+    use serde_json::{json, Value};
+
+    fn handle(session: &mut Session, request: Value) -> Value {
+        session.handle(&request.to_string())
+    }
+
+    #[test]
+    fn test_requests() {
+        let mut session = Session::new(Computation::Sum, ElementType::I64);
+
+        assert_eq!(
+            handle(
+                &mut session,
+                json!({"id": 1, "op": "build", "values": [1, 3, "7", 27]})
+            ),
+            json!({"id": 1, "ok": true, "result": 4})
+        );
+        assert_eq!(
+            handle(
+                &mut session,
+                json!({"id": "a", "op": "get", "left": 0, "right": 3})
+            ),
+            json!({"id": "a", "ok": true, "result": 38})
+        );
+        assert_eq!(
+            handle(&mut session, json!({"op": "modify", "pos": 3, "value": -1})),
+            json!({"id": null, "ok": true, "result": null})
+        );
+        assert_eq!(
+            handle(&mut session, json!({"op": "get", "left": 2, "right": 3})),
+            json!({"id": null, "ok": true, "result": 6})
+        );
+        assert_eq!(
+            handle(&mut session, json!({"op": "len"})),
+            json!({"id": null, "ok": true, "result": 4})
+        );
+    }
+
+    #[test]
+    fn test_build_overrides() {
+        let mut session = Session::new(Computation::Sum, ElementType::I64);
+        let build = json!({
+            "op": "build",
+            "values": ["170141183460469231731687303715884105727", 0],
+            "computation": "max",
+            "type": "i128",
+        });
+
+        assert_eq!(
+            handle(&mut session, build),
+            json!({"id": null, "ok": true, "result": 2})
+        );
+        assert_eq!(
+            handle(&mut session, json!({"op": "get", "left": 0, "right": 1})),
+            json!({"id": null, "ok": true, "result": "170141183460469231731687303715884105727"})
+        );
+    }
+
+    #[test]
+    fn test_overflow() {
+        let mut session = Session::new(Computation::MaxSliceSum, ElementType::I64);
+        let overflow = json!({"id": null, "ok": false, "error": {
+            "kind": "overflow",
+            "message": "Result overflows the element type",
+        }});
+
+        assert_eq!(
+            handle(
+                &mut session,
+                json!({"op": "build", "values": [i64::MAX, 1], "computation": "sum"})
+            ),
+            json!({"id": null, "ok": true, "result": 2})
+        );
+        assert_eq!(
+            handle(&mut session, json!({"op": "get", "left": 0, "right": 1})),
+            overflow
+        );
+
+        handle(
+            &mut session,
+            json!({"op": "build", "values": [i64::MAX, -1]}),
+        );
+
+        assert_eq!(
+            handle(&mut session, json!({"op": "modify", "pos": 1, "value": 1})),
+            json!({"id": null, "ok": true, "result": null})
+        );
+        assert_eq!(
+            handle(&mut session, json!({"op": "get", "left": 0, "right": 1})),
+            overflow
+        );
+        assert_eq!(
+            handle(&mut session, json!({"op": "get", "left": 0, "right": 0})),
+            json!({"id": null, "ok": true, "result": i64::MAX})
+        );
+    }
+
+    #[test]
+    fn test_errors() {
+        let mut session = Session::new(Computation::Sum, ElementType::U32);
+
+        assert_eq!(
+            handle(&mut session, json!({"id": 1, "op": "len"})),
+            json!({"id": 1, "ok": false, "error": {
+                "kind": "no_tree",
+                "message": "No tree has been built yet",
+            }})
+        );
+        assert_eq!(
+            handle(&mut session, json!({"op": "build", "values": [1, -2]})),
+            json!({"id": null, "ok": false, "error": {
+                "kind": "invalid_value",
+                "message": "Value <-2> can't be parsed as the element type",
+                "value": "-2",
+            }})
+        );
+
+        handle(&mut session, json!({"op": "build", "values": [1, 2]}));
+
+        assert_eq!(
+            handle(&mut session, json!({"op": "get", "left": 0, "right": 2})),
+            json!({"id": null, "ok": false, "error": {
+                "kind": "out_of_bounds",
+                "message": "Index 2 is out of bounds. It should be smaller or equal to 2",
+                "index": 2,
+                "len": 2,
+            }})
+        );
+        assert_eq!(
+            handle(&mut session, json!({"op": "get", "left": 1, "right": 0})),
+            json!({"id": null, "ok": false, "error": {
+                "kind": "invalid_range",
+                "message": "Left index <1> should be lower or equal to the right index <0>",
+                "left": 1,
+                "right": 0,
+            }})
+        );
+
+        let response = session.handle("{\"op\": \"pop\"}");
+        assert_eq!(response["ok"], json!(false));
+        assert_eq!(response["error"]["kind"], json!("invalid_request"));
+
+        let response = session.handle("not json");
+        assert_eq!(response["error"]["kind"], json!("invalid_request"));
+    }
+}
@@ -0,0 +1,311 @@
+use std::{
+    fs::File,
+    io::{self, BufRead, BufReader, IsTerminal},
+    net::{SocketAddr, TcpListener},
+    sync::Arc,
+};
+
+use error::CliError;
+use tree::{Computation, ElementType};
+
+mod checked;
+
+// The input-facing modules are public so the fuzz targets can drive them.
+pub mod error;
+pub mod json;
+pub mod repl;
+pub mod script;
+mod server;
+pub mod tree;
+
+const HELP: &str = "\
+Usage: segment-tree [COMMAND] [OPTIONS] [INPUT]
+
+Builds a segment tree and answers the queries read from INPUT (stdin by default).
+
+Commands:
+  run   Answer the queries from INPUT [default]
+  repl  Explore a tree interactively, type `help` inside for the commands
+  json  Answer JSON requests, one per line, with one JSON response per line
+  serve Share named trees with local clients over TCP or a Unix socket
+
+Options:
+  -c, --computation <NAME>  sum, max, min, max-slice-sum, xor, gcd, and, or [default: max-slice-sum]
+  -t, --type <TYPE>         i32, i64, i128, u32, u64, u128 [default: i64]
+  -f, --format <FORMAT>     classic or commands [default: classic]
+      --tcp <ADDRESS>       loopback address to serve on [default: 127.0.0.1:7878]
+      --unix <PATH>         Unix socket to serve on instead of TCP
+  -h, --help                Print this help
+
+Classic format:
+  n / n values / m / m queries, where a query is either `0 pos value`
+  (modify) or `1 left right` (get) with 1-based positions.
+
+Commands format:
+  One command per line with 0-based positions: `build v1 v2 ...`,
+  `get left right`, `set pos value` and `len`. Empty lines and lines
+  starting with `#` are skipped.
+
+JSON requests:
+  {\"op\": \"build\", \"values\": [..], \"computation\": .., \"type\": ..},
+  {\"op\": \"get\", \"left\": .., \"right\": ..}, {\"op\": \"modify\", \"pos\": .., \"value\": ..}
+  and {\"op\": \"len\"}. An optional \"id\" is echoed back in the response.
+
+Server commands:
+  `build name [-c NAME] [-t TYPE] v1 v2 ...`, `get name left right`,
+  `set name pos value`, `len name`, `drop name`, `list`, `quit` and
+  `shutdown`. Replies are `ok [result]` or `error message`.
+";
+
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+enum Command {
+    Run,
+    Repl,
+    Json,
+    Serve,
+}
+
+#[derive(Debug, PartialEq, Eq, Clone)]
+enum Address {
+    Tcp(String),
+    Unix(String),
+}
+
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+enum Format {
+    Classic,
+    Commands,
+}
+
+#[derive(Debug, PartialEq, Eq, Clone)]
+struct Options {
+    command: Command,
+    computation: Computation,
+    element_type: ElementType,
+    format: Format,
+    address: Address,
+    input: Option<String>,
+    help: bool,
+}
+
+impl Default for Options {
+    fn default() -> Self {
+        Self {
+            command: Command::Run,
+            computation: Computation::MaxSliceSum,
+            element_type: ElementType::I64,
+            format: Format::Classic,
+            address: Address::Tcp("127.0.0.1:7878".to_string()),
+            input: None,
+            help: false,
+        }
+    }
+}
+
+fn parse_options(args: impl IntoIterator<Item = String>) -> Result<Options, CliError> {
+    let mut options = Options::default();
+    let mut args = args.into_iter().peekable();
+
+    if let Some(command) =
+        args.next_if(|arg| matches!(arg.as_str(), "run" | "repl" | "json" | "serve"))
+    {
+        options.command = match command.as_str() {
+            "repl" => Command::Repl,
+            "json" => Command::Json,
+            "serve" => Command::Serve,
+            _ => Command::Run,
+        };
+    }
+
+    while let Some(arg) = args.next() {
+        let mut value = |name: &str| {
+            args.next()
+                .ok_or_else(|| CliError::Usage(format!("Option <{name}> requires a value")))
+        };
+
+        match arg.as_str() {
+            "-c" | "--computation" => {
+                options.computation = value(&arg)?.parse().map_err(CliError::Usage)?;
+            }
+            "-t" | "--type" => {
+                options.element_type = value(&arg)?.parse().map_err(CliError::Usage)?;
+            }
+            "-f" | "--format" => {
+                options.format = match value(&arg)?.as_str() {
+                    "classic" => Format::Classic,
+                    "commands" => Format::Commands,
+                    other => {
+                        return Err(CliError::Usage(format!(
+                            "Unknown format <{other}>, expected classic or commands"
+                        )))
+                    }
+                };
+            }
+            "--tcp" => options.address = Address::Tcp(value(&arg)?),
+            "--unix" => options.address = Address::Unix(value(&arg)?),
+            "-h" | "--help" => options.help = true,
+            other if other.starts_with('-') && other != "-" => {
+                return Err(CliError::Usage(format!("Unknown option <{other}>")));
+            }
+            _ if options.input.is_some() => {
+                return Err(CliError::Usage(format!("Unexpected argument <{arg}>")));
+            }
+            _ => options.input = Some(arg),
+        }
+    }
+
+    Ok(options)
+}
+
+pub fn run(args: impl IntoIterator<Item = String>) -> Result<(), CliError> {
+    let options = parse_options(args)?;
+
+    if options.help {
+        print!("{HELP}");
+        return Ok(());
+    }
+
+    if options.command == Command::Serve {
+        return serve(&options);
+    }
+
+    let input: Box<dyn BufRead> = match options.input.as_deref() {
+        None | Some("-") => Box::new(io::stdin().lock()),
+        Some(path) => Box::new(BufReader::new(File::open(path)?)),
+    };
+    let mut output = io::stdout().lock();
+
+    let (computation, element_type) = (options.computation, options.element_type);
+
+    match (options.command, options.format) {
+        (Command::Repl, _) => {
+            let prompt = options.input.is_none() && io::stdin().is_terminal();
+            repl::run_repl(input, &mut output, computation, element_type, prompt)
+        }
+        (Command::Json, _) => json::run_json(input, &mut output, computation, element_type),
+        (Command::Run, Format::Classic) => {
+            script::run_classic(input, &mut output, computation, element_type)
+        }
+        (Command::Run, Format::Commands) => {
+            script::run_commands(input, &mut output, computation, element_type)
+        }
+        (Command::Serve, _) => unreachable!("The server doesn't read INPUT"),
+    }
+}
+
+fn serve(options: &Options) -> Result<(), CliError> {
+    let registry = Arc::new(server::Registry::new(
+        options.computation,
+        options.element_type,
+    ));
+
+    match &options.address {
+        Address::Tcp(address) => {
+            let address: SocketAddr = address
+                .parse()
+                .map_err(|_| CliError::Usage(format!("Invalid TCP address <{address}>")))?;
+            if !address.ip().is_loopback() {
+                return Err(CliError::Usage(format!(
+                    "Refusing to serve on non-loopback address <{address}>"
+                )));
+            }
+
+            Ok(server::serve(TcpListener::bind(address)?, registry)?)
+        }
+        #[cfg(unix)]
+        Address::Unix(path) => {
+            let listener = std::os::unix::net::UnixListener::bind(path)?;
+            let result = server::serve(listener, registry);
+            std::fs::remove_file(path)?;
+
+            Ok(result?)
+        }
+        #[cfg(not(unix))]
+        Address::Unix(_) => Err(CliError::Usage(
+            "Unix sockets aren't supported on this platform".to_string(),
+        )),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{parse_options, Address, Command, Format, Options};
+    use crate::cli::tree::{Computation, ElementType};
+
+    fn parse(args: &[&str]) -> Result<Options, String> {
+        parse_options(args.iter().map(|arg| arg.to_string())).map_err(|e| e.to_string())
+    }
+
+    #[test]
+    fn test_default_options() {
+        assert_eq!(parse(&[]), Ok(Options::default()));
+    }
+
+    #[test]
+    fn test_parse_options() {
+        let expected = Options {
+            command: Command::Run,
+            computation: Computation::Gcd,
+            element_type: ElementType::U32,
+            format: Format::Commands,
+            address: Address::Tcp("127.0.0.1:7878".to_string()),
+            input: Some("queries.txt".to_string()),
+            help: false,
+        };
+
+        assert_eq!(
+            parse(&[
+                "-c",
+                "gcd",
+                "--type",
+                "u32",
+                "-f",
+                "commands",
+                "queries.txt"
+            ]),
+            Ok(expected)
+        );
+        assert_eq!(parse(&["--help"]).map(|options| options.help), Ok(true));
+        assert_eq!(
+            parse(&["repl", "-c", "sum"]).map(|options| options.command),
+            Ok(Command::Repl)
+        );
+        assert_eq!(
+            parse(&["json", "-t", "u64"]).map(|options| options.command),
+            Ok(Command::Json)
+        );
+        assert_eq!(
+            parse(&["serve", "--unix", "/tmp/tree.sock"]).map(|options| options.address),
+            Ok(Address::Unix("/tmp/tree.sock".to_string()))
+        );
+        assert_eq!(
+            parse(&["run", "input.txt"]).map(|options| options.input),
+            Ok(Some("input.txt".to_string()))
+        );
+    }
+
+    #[test]
+    fn test_parse_options_errors() {
+        let tests = [
+            (vec!["-c"], "Option <-c> requires a value"),
+            (
+                vec!["-c", "product"],
+                "Unknown computation <product>, expected one of: sum, max, min, max-slice-sum, xor, gcd, and, or",
+            ),
+            (
+                vec!["-t", "f64"],
+                "Unknown element type <f64>, expected one of: i32, i64, i128, u32, u64, u128",
+            ),
+            (
+                vec!["--format", "json"],
+                "Unknown format <json>, expected classic or commands",
+            ),
+            (vec!["--verbose"], "Unknown option <--verbose>"),
+            (vec!["a.txt", "b.txt"], "Unexpected argument <b.txt>"),
+        ];
+
+        for (args, expected) in tests {
+            assert_eq!(parse(&args), Err(expected.to_string()));
+        }
+    }
+}
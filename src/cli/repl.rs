@@ -0,0 +1,278 @@
+use std::io::{BufRead, Write};
+
+use super::{
+    error::CliError,
+    tree::{build_tree, Computation, DynTree, ElementType},
+};
+
+const HELP: &str = "\
+Commands (positions are 0-based):
+  load v1 v2 ...   build a new tree from the values
+  get left right   print the result for the range [left, right]
+  set pos value    replace the value at pos
+  dump             print every value and the result for the whole array
+  undo             revert the last load or set
+  history          print the commands entered so far
+  help             print this help
+  quit             leave the REPL";
+
+enum Change {
+    Load(Option<Box<dyn DynTree>>),
+    Set { pos: usize, prev_value: String },
+}
+
+pub struct Repl {
+    computation: Computation,
+    element_type: ElementType,
+    tree: Option<Box<dyn DynTree>>,
+    changes: Vec<Change>,
+    history: Vec<String>,
+}
+
+pub enum Response {
+    Output(String),
+    Quit,
+}
+
+impl Repl {
+    pub fn new(computation: Computation, element_type: ElementType) -> Self {
+        Self {
+            computation,
+            element_type,
+            tree: None,
+            changes: vec![],
+            history: vec![],
+        }
+    }
+
+    pub fn execute(&mut self, line: &str) -> Result<Response, String> {
+        let words: Vec<&str> = line.split_whitespace().collect();
+        let Some((&command, arguments)) = words.split_first() else {
+            return Ok(Response::Output(String::new()));
+        };
+
+        if command != "history" {
+            self.history.push(line.trim().to_string());
+        }
+
+        match (command, arguments) {
+            ("load", values) => {
+                let tree = build_tree(self.computation, self.element_type, values)
+                    .map_err(|e| e.to_string())?;
+                let prev_tree = self.tree.replace(tree);
+                self.changes.push(Change::Load(prev_tree));
+
+                Ok(Response::Output(format!("loaded {} values", values.len())))
+            }
+            ("get", [left, right]) => {
+                let (left, right) = (parse_index(left)?, parse_index(right)?);
+                let result = self.tree()?.describe(left, right);
+
+                result.map(Response::Output).map_err(|e| e.to_string())
+            }
+            ("set", [pos, value]) => {
+                let pos = parse_index(pos)?;
+                let prev_value = self.tree_mut()?.modify(pos, value);
+                let prev_value = prev_value.map_err(|e| e.to_string())?;
+                let output = format!("{pos}: {prev_value} -> {value}");
+                self.changes.push(Change::Set { pos, prev_value });
+
+                Ok(Response::Output(output))
+            }
+            ("dump", []) => {
+                let tree = self.tree()?;
+                let mut lines: Vec<String> = tree
+                    .values()
+                    .iter()
+                    .enumerate()
+                    .map(|(pos, value)| format!("{pos}: {value}"))
+                    .collect();
+                if tree.len() > 0 {
+                    let result = tree.describe(0, tree.len() - 1);
+                    lines.push(format!("[0, {}]:", tree.len() - 1));
+                    lines.push(result.map_err(|e| e.to_string())?);
+                }
+
+                Ok(Response::Output(lines.join("\n")))
+            }
+            ("undo", []) => match self.changes.pop() {
+                Some(Change::Load(prev_tree)) => {
+                    self.tree = prev_tree;
+                    Ok(Response::Output("undone load".to_string()))
+                }
+                Some(Change::Set { pos, prev_value }) => {
+                    let value = self.tree_mut()?.modify(pos, &prev_value);
+                    let value = value.map_err(|e| e.to_string())?;
+
+                    Ok(Response::Output(format!("{pos}: {value} -> {prev_value}")))
+                }
+                None => Err("Nothing to undo".to_string()),
+            },
+            ("history", []) => {
+                let lines: Vec<String> = self
+                    .history
+                    .iter()
+                    .enumerate()
+                    .map(|(index, command)| format!("{:>4}  {command}", index + 1))
+                    .collect();
+
+                Ok(Response::Output(lines.join("\n")))
+            }
+            ("help", []) => Ok(Response::Output(HELP.to_string())),
+            ("quit" | "exit", []) => Ok(Response::Quit),
+            ("get" | "set" | "dump" | "undo" | "history" | "help" | "quit" | "exit", _) => Err(
+                format!("Wrong number of arguments for <{command}>, see <help>"),
+            ),
+            (other, _) => Err(format!("Unknown command <{other}>, see <help>")),
+        }
+    }
+
+    fn tree(&self) -> Result<&dyn DynTree, String> {
+        self.tree
+            .as_deref()
+            .ok_or_else(|| "No tree has been loaded yet, use <load>".to_string())
+    }
+
+    fn tree_mut(&mut self) -> Result<&mut Box<dyn DynTree>, String> {
+        self.tree
+            .as_mut()
+            .ok_or_else(|| "No tree has been loaded yet, use <load>".to_string())
+    }
+}
+
+fn parse_index(value: &str) -> Result<usize, String> {
+    value
+        .parse()
+        .map_err(|_| format!("Expected a position, found <{value}>"))
+}
+
+pub fn run_repl(
+    input: impl BufRead,
+    output: &mut impl Write,
+    computation: Computation,
+    element_type: ElementType,
+    prompt: bool,
+) -> Result<(), CliError> {
+    let mut repl = Repl::new(computation, element_type);
+    let mut lines = input.lines();
+
+    loop {
+        if prompt {
+            write!(output, "> ")?;
+            output.flush()?;
+        }
+
+        let Some(line) = lines.next() else {
+            return Ok(());
+        };
+
+        match repl.execute(&line?) {
+            Ok(Response::Output(text)) if text.is_empty() => {}
+            Ok(Response::Output(text)) => writeln!(output, "{text}")?,
+            Ok(Response::Quit) => return Ok(()),
+            Err(message) => writeln!(output, "error: {message}")?,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::run_repl;
+    use crate::cli::tree::{Computation, ElementType};
+
+    fn session(input: &str, computation: Computation) -> String {
+        let mut output = vec![];
+        run_repl(
+            input.as_bytes(),
+            &mut output,
+            computation,
+            ElementType::I64,
+            false,
+        )
+        .unwrap();
+        String::from_utf8(output).unwrap()
+    }
+
+    #[test]
+    fn test_session() {
+        let input = "load 1 3 7 27\nget 0 3\nset 3 -1\nget 2 3\nundo\nget 2 3\nquit\nget 0 0\n";
+        let expected = "\
+loaded 4 values
+38
+3: 27 -> -1
+6
+3: -1 -> 27
+34
+";
+
+        assert_eq!(session(input, Computation::Sum), expected);
+    }
+
+    #[test]
+    fn test_dump_and_history() {
+        let input = "load -1 2 3\n\ndump\nhistory\n";
+        let expected = "\
+loaded 3 values
+0: -1
+1: 2
+2: 3
+[0, 2]:
+best_sum:    5
+total_sum:   4
+best_prefix: 4
+best_suffix: 5
+   1  load -1 2 3
+   2  dump
+";
+
+        assert_eq!(session(input, Computation::MaxSliceSum), expected);
+    }
+
+    #[test]
+    fn test_undo_load() {
+        let input = "load 1 2\nload 5\nget 0 1\nundo\nget 0 1\nundo\nget 0 0\nundo\n";
+        let expected = "\
+loaded 2 values
+loaded 1 values
+error: Index 1 is out of bounds. It should be smaller or equal to 1
+undone load
+3
+undone load
+error: No tree has been loaded yet, use <load>
+error: Nothing to undo
+";
+
+        assert_eq!(session(input, Computation::Sum), expected);
+    }
+
+    #[test]
+    fn test_errors() {
+        let input = "load 1 x\nload 1 2\nget 1 0\nset 5 1\nset 0 y\nget a 1\nget 0\npop\n";
+        let expected = "\
+error: Value <x> can't be parsed as the element type
+loaded 2 values
+error: Left index <1> should be lower or equal to the right index <0>
+error: Index 5 is out of bounds. It should be smaller or equal to 2
+error: Value <y> can't be parsed as the element type
+error: Expected a position, found <a>
+error: Wrong number of arguments for <get>, see <help>
+error: Unknown command <pop>, see <help>
+";
+
+        assert_eq!(session(input, Computation::Sum), expected);
+    }
+
+    #[test]
+    fn test_overflow() {
+        let input = "load 9223372036854775807 1\nget 0 1\ndump\nset 1 -1\nget 0 1\n";
+        let expected = "\
+loaded 2 values
+error: Result overflows the element type
+error: Result overflows the element type
+1: 1 -> -1
+9223372036854775806
+";
+
+        assert_eq!(session(input, Computation::Sum), expected);
+    }
+}
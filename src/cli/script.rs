@@ -0,0 +1,346 @@
+use std::io::{BufRead, Write};
+
+use super::{
+    error::CliError,
+    tree::{build_tree, Computation, DynTree, ElementType},
+};
+
+struct NumberedLines<R> {
+    lines: std::io::Lines<R>,
+    line: usize,
+}
+
+impl<R: BufRead> NumberedLines<R> {
+    fn new(input: R) -> Self {
+        Self {
+            lines: input.lines(),
+            line: 0,
+        }
+    }
+
+    fn next_line(&mut self) -> Result<Option<(usize, String)>, CliError> {
+        match self.lines.next() {
+            Some(line) => {
+                self.line += 1;
+                Ok(Some((self.line, line?)))
+            }
+            None => Ok(None),
+        }
+    }
+
+    fn expect_line(&mut self, expected: &str) -> Result<(usize, String), CliError> {
+        self.next_line()?.ok_or_else(|| {
+            CliError::parse(
+                self.line + 1,
+                format!("Unexpected end of input, expected {expected}"),
+            )
+        })
+    }
+}
+
+fn parse_index(line: usize, value: &str) -> Result<usize, CliError> {
+    value
+        .parse()
+        .map_err(|_| CliError::parse(line, format!("Expected a position, found <{value}>")))
+}
+
+fn parse_one_based_index(line: usize, value: &str) -> Result<usize, CliError> {
+    match parse_index(line, value)? {
+        0 => Err(CliError::parse(line, "Positions are 1-based")),
+        index => Ok(index - 1),
+    }
+}
+
+fn expect_arguments<'a>(
+    line: usize,
+    command: &str,
+    arguments: &'a [&'a str],
+    count: usize,
+) -> Result<&'a [&'a str], CliError> {
+    if arguments.len() == count {
+        Ok(arguments)
+    } else {
+        Err(CliError::parse(
+            line,
+            format!(
+                "Command <{command}> expects {count} arguments, found {}",
+                arguments.len()
+            ),
+        ))
+    }
+}
+
+pub fn run_classic(
+    input: impl BufRead,
+    output: &mut impl Write,
+    computation: Computation,
+    element_type: ElementType,
+) -> Result<(), CliError> {
+    let mut lines = NumberedLines::new(input);
+
+    let (line, n) = lines.expect_line("the sequence length")?;
+    let n = parse_index(line, n.trim())?;
+
+    let (line, sequence) = lines.expect_line("the sequence")?;
+    let values: Vec<&str> = sequence.split_whitespace().collect();
+    if values.len() != n {
+        return Err(CliError::parse(
+            line,
+            format!("Expected {n} values, found {}", values.len()),
+        ));
+    }
+    let mut tree =
+        build_tree(computation, element_type, &values).map_err(|e| CliError::tree(line, e))?;
+
+    let (line, m) = lines.expect_line("the number of queries")?;
+    let m = parse_index(line, m.trim())?;
+
+    for _ in 0..m {
+        let (line, query) = lines.expect_line("a query")?;
+        let query: Vec<&str> = query.split_whitespace().collect();
+        let query = expect_arguments(line, "query", &query, 3)?;
+
+        match query[0] {
+            "0" => {
+                let pos = parse_one_based_index(line, query[1])?;
+                tree.modify(pos, query[2])
+                    .map_err(|e| CliError::tree(line, e))?;
+            }
+            "1" => {
+                let left = parse_one_based_index(line, query[1])?;
+                let right = parse_one_based_index(line, query[2])?;
+                let result = tree.get(left, right).map_err(|e| CliError::tree(line, e))?;
+                writeln!(output, "{result}")?;
+            }
+            other => {
+                return Err(CliError::parse(
+                    line,
+                    format!("Unexpected query type <{other}>, expected 0 or 1"),
+                ))
+            }
+        }
+    }
+
+    Ok(())
+}
+
+pub fn run_commands(
+    input: impl BufRead,
+    output: &mut impl Write,
+    computation: Computation,
+    element_type: ElementType,
+) -> Result<(), CliError> {
+    let mut lines = NumberedLines::new(input);
+    let mut tree: Option<Box<dyn DynTree>> = None;
+
+    while let Some((line, text)) = lines.next_line()? {
+        let words: Vec<&str> = text.split_whitespace().collect();
+        let Some((&command, arguments)) = words.split_first() else {
+            continue;
+        };
+        if command.starts_with('#') {
+            continue;
+        }
+
+        if command == "build" {
+            tree = Some(
+                build_tree(computation, element_type, arguments)
+                    .map_err(|e| CliError::tree(line, e))?,
+            );
+            continue;
+        }
+
+        let tree = tree
+            .as_mut()
+            .ok_or_else(|| CliError::parse(line, "No tree has been built yet, use <build>"))?;
+
+        match command {
+            "get" => {
+                let arguments = expect_arguments(line, command, arguments, 2)?;
+                let left = parse_index(line, arguments[0])?;
+                let right = parse_index(line, arguments[1])?;
+                let result = tree.get(left, right).map_err(|e| CliError::tree(line, e))?;
+                writeln!(output, "{result}")?;
+            }
+            "set" => {
+                let arguments = expect_arguments(line, command, arguments, 2)?;
+                let pos = parse_index(line, arguments[0])?;
+                tree.modify(pos, arguments[1])
+                    .map_err(|e| CliError::tree(line, e))?;
+            }
+            "len" => {
+                expect_arguments(line, command, arguments, 0)?;
+                writeln!(output, "{}", tree.len())?;
+            }
+            other => {
+                return Err(CliError::parse(
+                    line,
+                    format!("Unknown command <{other}>, expected build, get, set or len"),
+                ))
+            }
+        }
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{run_classic, run_commands};
+    use crate::cli::{
+        error::CliError,
+        tree::{Computation, ElementType},
+    };
+
+    fn classic(input: &str, computation: Computation) -> Result<String, String> {
+        let mut output = vec![];
+        run_classic(input.as_bytes(), &mut output, computation, ElementType::I64)
+            .map_err(|e| e.to_string())?;
+        Ok(String::from_utf8(output).unwrap())
+    }
+
+    fn commands(input: &str, computation: Computation) -> Result<String, String> {
+        let mut output = vec![];
+        run_commands(input.as_bytes(), &mut output, computation, ElementType::I64)
+            .map_err(|e: CliError| e.to_string())?;
+        Ok(String::from_utf8(output).unwrap())
+    }
+
+    #[test]
+    fn test_classic_format() {
+        let input = "5\n-1 2 3 -4 5\n4\n1 1 5\n0 4 10\n1 1 5\n1 1 1\n";
+
+        assert_eq!(
+            classic(input, Computation::MaxSliceSum),
+            Ok("6\n20\n-1\n".to_string())
+        );
+        assert_eq!(
+            classic(input, Computation::Sum),
+            Ok("5\n19\n-1\n".to_string())
+        );
+    }
+
+    #[test]
+    fn test_classic_format_errors() {
+        let tests = [
+            (
+                "",
+                "line 1: Unexpected end of input, expected the sequence length",
+            ),
+            ("x\n", "line 1: Expected a position, found <x>"),
+            ("3\n1 2\n", "line 2: Expected 3 values, found 2"),
+            (
+                "2\n1 a\n",
+                "line 2: Value <a> can't be parsed as the element type",
+            ),
+            (
+                "2\n1 2\n1\n",
+                "line 4: Unexpected end of input, expected a query",
+            ),
+            (
+                "2\n1 2\n1\n2 1 1\n",
+                "line 4: Unexpected query type <2>, expected 0 or 1",
+            ),
+            ("2\n1 2\n1\n1 0 1\n", "line 4: Positions are 1-based"),
+            (
+                "2\n1 2\n2\n1 1 2\n1 1 3\n",
+                "line 5: Index 2 is out of bounds. It should be smaller or equal to 2",
+            ),
+            (
+                "2\n1 2\n1\n1 1\n",
+                "line 4: Command <query> expects 3 arguments, found 2",
+            ),
+        ];
+
+        for (input, expected) in tests {
+            assert_eq!(classic(input, Computation::Sum), Err(expected.to_string()));
+        }
+    }
+
+    #[test]
+    fn test_commands_format() {
+        let input = "# comment\nbuild 1 3 7 27\n\nget 0 3\nset 3 -1\nget 2 3\nlen\n";
+
+        assert_eq!(
+            commands(input, Computation::Sum),
+            Ok("38\n6\n4\n".to_string())
+        );
+        assert_eq!(
+            commands(input, Computation::Max),
+            Ok("27\n7\n4\n".to_string())
+        );
+    }
+
+    #[test]
+    fn test_commands_format_errors() {
+        let tests = [
+            (
+                "get 0 1\n",
+                "line 1: No tree has been built yet, use <build>",
+            ),
+            (
+                "build 1 2\nget 1 0\n",
+                "line 2: Left index <1> should be lower or equal to the right index <0>",
+            ),
+            (
+                "build 1 2\nset 0\n",
+                "line 2: Command <set> expects 2 arguments, found 1",
+            ),
+            (
+                "build 1 2\nremove 0\n",
+                "line 2: Unknown command <remove>, expected build, get, set or len",
+            ),
+        ];
+
+        for (input, expected) in tests {
+            assert_eq!(commands(input, Computation::Sum), Err(expected.to_string()));
+        }
+    }
+
+    #[test]
+    fn test_overflow() {
+        let max = i64::MAX;
+        let min = i64::MIN;
+
+        for computation in [Computation::Sum, Computation::MaxSliceSum] {
+            assert_eq!(
+                commands(&format!("build {max} 1\nget 1 1\nget 0 1\n"), computation),
+                Err("line 3: Result overflows the element type".to_string())
+            );
+            assert_eq!(
+                commands(&format!("build {max} 1\nset 1 -1\nget 0 1\n"), computation),
+                Ok(format!(
+                    "{}\n",
+                    if computation == Computation::Sum {
+                        max - 1
+                    } else {
+                        max
+                    }
+                ))
+            );
+        }
+
+        assert_eq!(
+            commands(
+                &format!("build {min} -1 5\nget 0 2\n"),
+                Computation::MaxSliceSum
+            ),
+            Ok("5\n".to_string())
+        );
+        assert_eq!(
+            commands(&format!("build {min} -1 5\nget 0 2\n"), Computation::Sum),
+            Err("line 2: Result overflows the element type".to_string())
+        );
+        assert_eq!(
+            commands(&format!("build {min} -1\nget 0 1\n"), Computation::Gcd),
+            Ok("1\n".to_string())
+        );
+        assert_eq!(
+            classic(
+                &format!("2\n{max} {max}\n1\n1 1 2\n"),
+                Computation::MaxSliceSum
+            ),
+            Err("line 4: Result overflows the element type".to_string())
+        );
+    }
+}
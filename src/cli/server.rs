@@ -0,0 +1,503 @@
+use std::{
+    collections::HashMap,
+    io::{self, BufRead, BufReader, ErrorKind, Read, Write},
+    net::{Shutdown, TcpListener, TcpStream},
+    sync::{
+        atomic::{AtomicBool, AtomicUsize, Ordering},
+        Arc, Mutex, PoisonError, RwLock,
+    },
+    thread::{self, JoinHandle},
+    time::Duration,
+};
+
+#[cfg(unix)]
+use std::os::unix::net::{UnixListener, UnixStream};
+
+use super::tree::{build_tree, Computation, DynTree, ElementType};
+
+const ACCEPT_POLL_INTERVAL: Duration = Duration::from_millis(20);
+
+type SharedTree = Arc<RwLock<Box<dyn DynTree>>>;
+
+pub trait Connection: Read + Write + Send + Sized + 'static {
+    fn try_clone(&self) -> io::Result<Self>;
+
+    fn set_nonblocking(&self, nonblocking: bool) -> io::Result<()>;
+
+    fn close(&self) -> io::Result<()>;
+}
+
+pub trait Listener {
+    type Connection: Connection;
+
+    fn accept(&self) -> io::Result<Self::Connection>;
+
+    fn set_nonblocking(&self, nonblocking: bool) -> io::Result<()>;
+}
+
+impl Connection for TcpStream {
+    fn try_clone(&self) -> io::Result<Self> {
+        TcpStream::try_clone(self)
+    }
+
+    fn set_nonblocking(&self, nonblocking: bool) -> io::Result<()> {
+        TcpStream::set_nonblocking(self, nonblocking)
+    }
+
+    fn close(&self) -> io::Result<()> {
+        self.shutdown(Shutdown::Both)
+    }
+}
+
+impl Listener for TcpListener {
+    type Connection = TcpStream;
+
+    fn accept(&self) -> io::Result<TcpStream> {
+        TcpListener::accept(self).map(|(stream, _)| stream)
+    }
+
+    fn set_nonblocking(&self, nonblocking: bool) -> io::Result<()> {
+        TcpListener::set_nonblocking(self, nonblocking)
+    }
+}
+
+#[cfg(unix)]
+impl Connection for UnixStream {
+    fn try_clone(&self) -> io::Result<Self> {
+        UnixStream::try_clone(self)
+    }
+
+    fn set_nonblocking(&self, nonblocking: bool) -> io::Result<()> {
+        UnixStream::set_nonblocking(self, nonblocking)
+    }
+
+    fn close(&self) -> io::Result<()> {
+        self.shutdown(Shutdown::Both)
+    }
+}
+
+#[cfg(unix)]
+impl Listener for UnixListener {
+    type Connection = UnixStream;
+
+    fn accept(&self) -> io::Result<UnixStream> {
+        UnixListener::accept(self).map(|(stream, _)| stream)
+    }
+
+    fn set_nonblocking(&self, nonblocking: bool) -> io::Result<()> {
+        UnixListener::set_nonblocking(self, nonblocking)
+    }
+}
+
+enum Action {
+    Continue,
+    Close,
+    Shutdown,
+}
+
+/// Named trees shared by every connection. Queries on a tree run in parallel
+/// under its read lock, modifications are serialized by its write lock.
+///
+/// Tree operations report errors instead of panicking, so a poisoned lock can
+/// only come from a bug in a handler and the trees behind it are still served.
+pub struct Registry {
+    computation: Computation,
+    element_type: ElementType,
+    trees: RwLock<HashMap<String, SharedTree>>,
+}
+
+impl Registry {
+    pub fn new(computation: Computation, element_type: ElementType) -> Self {
+        Self {
+            computation,
+            element_type,
+            trees: RwLock::new(HashMap::new()),
+        }
+    }
+
+    fn execute(&self, line: &str) -> (String, Action) {
+        let words: Vec<&str> = line.split_whitespace().collect();
+
+        let result = match words.as_slice() {
+            ["build", name, arguments @ ..] => self.build(name, arguments),
+            ["get", name, left, right] => self.tree(name).and_then(|tree| {
+                let (left, right) = (parse_index(left)?, parse_index(right)?);
+                let tree = tree.read().unwrap_or_else(PoisonError::into_inner);
+
+                tree.get(left, right).map_err(|e| e.to_string())
+            }),
+            ["set", name, pos, value] => self.tree(name).and_then(|tree| {
+                let pos = parse_index(pos)?;
+                let mut tree = tree.write().unwrap_or_else(PoisonError::into_inner);
+
+                tree.modify(pos, value)
+                    .map(|_| String::new())
+                    .map_err(|e| e.to_string())
+            }),
+            ["len", name] => self.tree(name).map(|tree| {
+                let tree = tree.read().unwrap_or_else(PoisonError::into_inner);
+                tree.len().to_string()
+            }),
+            ["drop", name] => {
+                let mut trees = self.trees.write().unwrap_or_else(PoisonError::into_inner);
+                match trees.remove(*name) {
+                    Some(_) => Ok(String::new()),
+                    None => Err(format!("Unknown tree <{name}>")),
+                }
+            }
+            ["list"] => {
+                let trees = self.trees.read().unwrap_or_else(PoisonError::into_inner);
+                let mut names: Vec<&str> = trees.keys().map(String::as_str).collect();
+                names.sort_unstable();
+                Ok(names.join(" "))
+            }
+            ["quit"] => return ("ok".to_string(), Action::Close),
+            ["shutdown"] => return ("ok".to_string(), Action::Shutdown),
+            [] => Err("Empty command".to_string()),
+            [command, ..] => Err(format!("Unknown or malformed command <{command}>")),
+        };
+
+        let reply = match result {
+            Ok(payload) if payload.is_empty() => "ok".to_string(),
+            Ok(payload) => format!("ok {payload}"),
+            Err(message) => format!("error {message}"),
+        };
+
+        (reply, Action::Continue)
+    }
+
+    fn build(&self, name: &str, mut arguments: &[&str]) -> Result<String, String> {
+        let mut computation = self.computation;
+        let mut element_type = self.element_type;
+
+        loop {
+            match arguments {
+                ["-c" | "--computation", value, rest @ ..] => {
+                    computation = value.parse()?;
+                    arguments = rest;
+                }
+                ["-t" | "--type", value, rest @ ..] => {
+                    element_type = value.parse()?;
+                    arguments = rest;
+                }
+                _ => break,
+            }
+        }
+
+        let tree = build_tree(computation, element_type, arguments).map_err(|e| e.to_string())?;
+        let len = tree.len();
+        let mut trees = self.trees.write().unwrap_or_else(PoisonError::into_inner);
+        trees.insert(name.to_string(), Arc::new(RwLock::new(tree)));
+
+        Ok(len.to_string())
+    }
+
+    fn tree(&self, name: &str) -> Result<SharedTree, String> {
+        let trees = self.trees.read().unwrap_or_else(PoisonError::into_inner);
+        trees
+            .get(name)
+            .cloned()
+            .ok_or_else(|| format!("Unknown tree <{name}>"))
+    }
+}
+
+fn parse_index(value: &str) -> Result<usize, String> {
+    value
+        .parse()
+        .map_err(|_| format!("Expected a position, found <{value}>"))
+}
+
+struct ServerState<S> {
+    registry: Arc<Registry>,
+    shutdown: AtomicBool,
+    next_id: AtomicUsize,
+    connections: Mutex<HashMap<usize, S>>,
+}
+
+fn handle_connection<S: Connection>(state: &ServerState<S>, stream: S) -> io::Result<()> {
+    let mut writer = stream.try_clone()?;
+    let reader = BufReader::new(stream);
+
+    for line in reader.lines() {
+        let (reply, action) = state.registry.execute(&line?);
+        writer.write_all(format!("{reply}\n").as_bytes())?;
+
+        match action {
+            Action::Continue => {}
+            Action::Close => break,
+            Action::Shutdown => {
+                state.shutdown.store(true, Ordering::SeqCst);
+                break;
+            }
+        }
+    }
+
+    Ok(())
+}
+
+/// Accepts connections until a client sends `shutdown`, then closes every
+/// open connection and waits for their handlers to finish.
+pub fn serve<L>(listener: L, registry: Arc<Registry>) -> io::Result<()>
+where
+    L: Listener,
+{
+    let state = Arc::new(ServerState {
+        registry,
+        shutdown: AtomicBool::new(false),
+        next_id: AtomicUsize::new(0),
+        connections: Mutex::new(HashMap::new()),
+    });
+    let mut handlers: Vec<JoinHandle<()>> = vec![];
+
+    listener.set_nonblocking(true)?;
+
+    while !state.shutdown.load(Ordering::SeqCst) {
+        let stream = match listener.accept() {
+            Ok(stream) => stream,
+            Err(error) if error.kind() == ErrorKind::WouldBlock => {
+                thread::sleep(ACCEPT_POLL_INTERVAL);
+                continue;
+            }
+            // Failures such as running out of file descriptors only affect the
+            // connection being accepted, so keep serving the others.
+            Err(error) => {
+                eprintln!("accept: {error}");
+                thread::sleep(ACCEPT_POLL_INTERVAL);
+                continue;
+            }
+        };
+
+        let id = state.next_id.fetch_add(1, Ordering::SeqCst);
+        let registered = stream
+            .set_nonblocking(false)
+            .and_then(|()| stream.try_clone())
+            .map(|clone| {
+                let connections = state.connections.lock();
+                connections
+                    .unwrap_or_else(PoisonError::into_inner)
+                    .insert(id, clone);
+            });
+        if let Err(error) = registered {
+            eprintln!("connection {id}: {error}");
+            continue;
+        }
+
+        let state = Arc::clone(&state);
+        handlers.push(thread::spawn(move || {
+            if let Err(error) = handle_connection(&state, stream) {
+                eprintln!("connection {id}: {error}");
+            }
+
+            let connections = state.connections.lock();
+            connections
+                .unwrap_or_else(PoisonError::into_inner)
+                .remove(&id);
+        }));
+        handlers.retain(|handler| !handler.is_finished());
+    }
+
+    let connections = state.connections.lock();
+    for connection in connections.unwrap_or_else(PoisonError::into_inner).values() {
+        connection.close().ok();
+    }
+
+    for handler in handlers {
+        handler.join().ok();
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{serve, Registry};
+    use crate::cli::tree::{Computation, ElementType};
+    use std::{
+        io::{BufRead, BufReader, Write},
+        net::{TcpListener, TcpStream},
+        sync::Arc,
+        thread,
+    };
+
+    struct Client {
+        reader: BufReader<TcpStream>,
+        writer: TcpStream,
+    }
+
+    impl Client {
+        fn connect(address: &str) -> Self {
+            let writer = TcpStream::connect(address).unwrap();
+            let reader = BufReader::new(writer.try_clone().unwrap());
+
+            Self { reader, writer }
+        }
+
+        fn send(&mut self, command: &str) -> String {
+            self.writer
+                .write_all(format!("{command}\n").as_bytes())
+                .unwrap();
+
+            let mut reply = String::new();
+            self.reader.read_line(&mut reply).unwrap();
+            reply.trim_end().to_string()
+        }
+    }
+
+    fn start_server() -> (String, thread::JoinHandle<()>) {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let address = listener.local_addr().unwrap().to_string();
+        let registry = Arc::new(Registry::new(Computation::Sum, ElementType::I64));
+        let server = thread::spawn(move || serve(listener, registry).unwrap());
+
+        (address, server)
+    }
+
+    #[test]
+    fn test_commands() {
+        let (address, server) = start_server();
+        let mut client = Client::connect(&address);
+
+        assert_eq!(client.send("build a 1 3 7 27"), "ok 4");
+        assert_eq!(client.send("build b -c max -t u32 5 1 9"), "ok 3");
+        assert_eq!(client.send("list"), "ok a b");
+        assert_eq!(client.send("get a 0 3"), "ok 38");
+        assert_eq!(client.send("set a 3 -1"), "ok");
+        assert_eq!(client.send("get a 2 3"), "ok 6");
+        assert_eq!(client.send("get b 0 2"), "ok 9");
+        assert_eq!(client.send("len b"), "ok 3");
+        assert_eq!(
+            client.send("get a 3 4"),
+            "error Index 4 is out of bounds. It should be smaller or equal to 4"
+        );
+        assert_eq!(
+            client.send("set b 0 -1"),
+            "error Value <-1> can't be parsed as the element type"
+        );
+        assert_eq!(client.send("drop b"), "ok");
+        assert_eq!(client.send("get b 0 0"), "error Unknown tree <b>");
+        assert_eq!(
+            client.send("get a"),
+            "error Unknown or malformed command <get>"
+        );
+        assert_eq!(client.send("quit"), "ok");
+
+        let mut client = Client::connect(&address);
+        assert_eq!(client.send("shutdown"), "ok");
+
+        server.join().unwrap();
+    }
+
+    #[test]
+    fn test_concurrent_clients() {
+        let (address, server) = start_server();
+        let mut admin = Client::connect(&address);
+        let values: Vec<String> = (0..100).map(|value| value.to_string()).collect();
+
+        assert_eq!(
+            admin.send(&format!("build shared {}", values.join(" "))),
+            "ok 100"
+        );
+
+        let writers: Vec<_> = (0..4)
+            .map(|writer| {
+                let address = address.clone();
+                thread::spawn(move || {
+                    let mut client = Client::connect(&address);
+                    for pos in (writer..100).step_by(4) {
+                        assert_eq!(client.send(&format!("set shared {pos} 1")), "ok");
+                    }
+                })
+            })
+            .collect();
+        let readers: Vec<_> = (0..4)
+            .map(|_| {
+                let address = address.clone();
+                thread::spawn(move || {
+                    let mut client = Client::connect(&address);
+                    for _ in 0..50 {
+                        let reply = client.send("get shared 0 99");
+                        let sum: i64 = reply.strip_prefix("ok ").unwrap().parse().unwrap();
+                        assert!((100..=4950).contains(&sum));
+                    }
+                })
+            })
+            .collect();
+
+        for handle in writers.into_iter().chain(readers) {
+            handle.join().unwrap();
+        }
+
+        assert_eq!(admin.send("get shared 0 99"), "ok 100");
+
+        let idle = Client::connect(&address);
+        assert_eq!(admin.send("shutdown"), "ok");
+        server.join().unwrap();
+        drop(idle);
+    }
+
+    #[test]
+    fn test_overflow_keeps_serving() {
+        let (address, server) = start_server();
+        let mut client = Client::connect(&address);
+
+        assert_eq!(client.send(&format!("build a {} 0", i64::MAX)), "ok 2");
+        assert_eq!(client.send("set a 1 1"), "ok");
+        assert_eq!(
+            client.send("get a 0 1"),
+            "error Result overflows the element type"
+        );
+        assert_eq!(client.send("get a 0 0"), format!("ok {}", i64::MAX));
+
+        let mut client = Client::connect(&address);
+        assert_eq!(client.send("set a 1 -1"), "ok");
+        assert_eq!(client.send("get a 0 1"), format!("ok {}", i64::MAX - 1));
+        assert_eq!(client.send("shutdown"), "ok");
+
+        server.join().unwrap();
+    }
+
+    #[test]
+    fn test_poisoned_tree_lock() {
+        let registry = Registry::new(Computation::Sum, ElementType::I64);
+        registry.execute("build a 1 2");
+
+        let tree = registry.tree("a").unwrap();
+        thread::spawn(move || {
+            let _guard = tree.write().unwrap();
+            panic!("Poisoning the tree lock");
+        })
+        .join()
+        .unwrap_err();
+
+        assert_eq!(registry.execute("get a 0 1").0, "ok 3");
+        assert_eq!(registry.execute("set a 0 5").0, "ok");
+        assert_eq!(registry.execute("get a 0 1").0, "ok 7");
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_unix_socket() {
+        use std::os::unix::net::{UnixListener, UnixStream};
+
+        let path = std::env::temp_dir().join(format!("segment-tree-{}.sock", std::process::id()));
+        let listener = UnixListener::bind(&path).unwrap();
+        let registry = Arc::new(Registry::new(Computation::Max, ElementType::I32));
+        let server = thread::spawn(move || serve(listener, registry).unwrap());
+
+        let mut writer = UnixStream::connect(&path).unwrap();
+        let mut reader = BufReader::new(writer.try_clone().unwrap());
+        let mut reply = String::new();
+
+        writer
+            .write_all(b"build t 4 -2 8\nget t 0 1\nshutdown\n")
+            .unwrap();
+        for expected in ["ok 3\n", "ok 4\n", "ok\n"] {
+            reply.clear();
+            reader.read_line(&mut reply).unwrap();
+            assert_eq!(reply, expected);
+        }
+
+        server.join().unwrap();
+        std::fs::remove_file(path).unwrap();
+    }
+}
@@ -0,0 +1,267 @@
+use std::{fmt::Display, str::FromStr};
+
+use segment_tree::{
+    BitAndComputation, BitOrComputation, GcdComputation, MaxComputation, MaxSliceSum,
+    MaxSliceSumComputation, MinComputation, SegmentTree, SegmentTreeComputation, SumComputation,
+    XorComputation,
+};
+
+use super::{
+    checked::{Checked, CheckedAdd},
+    error::TreeError,
+};
+
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub enum Computation {
+    Sum,
+    Max,
+    Min,
+    MaxSliceSum,
+    Xor,
+    Gcd,
+    BitAnd,
+    BitOr,
+}
+
+impl Computation {
+    pub const NAMES: [&'static str; 8] = [
+        "sum",
+        "max",
+        "min",
+        "max-slice-sum",
+        "xor",
+        "gcd",
+        "and",
+        "or",
+    ];
+}
+
+impl FromStr for Computation {
+    type Err = String;
+
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        match value {
+            "sum" => Ok(Self::Sum),
+            "max" => Ok(Self::Max),
+            "min" => Ok(Self::Min),
+            "max-slice-sum" => Ok(Self::MaxSliceSum),
+            "xor" => Ok(Self::Xor),
+            "gcd" => Ok(Self::Gcd),
+            "and" => Ok(Self::BitAnd),
+            "or" => Ok(Self::BitOr),
+            _ => Err(format!(
+                "Unknown computation <{value}>, expected one of: {}",
+                Self::NAMES.join(", ")
+            )),
+        }
+    }
+}
+
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub enum ElementType {
+    I32,
+    I64,
+    I128,
+    U32,
+    U64,
+    U128,
+}
+
+impl ElementType {
+    pub const NAMES: [&'static str; 6] = ["i32", "i64", "i128", "u32", "u64", "u128"];
+}
+
+impl FromStr for ElementType {
+    type Err = String;
+
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        match value {
+            "i32" => Ok(Self::I32),
+            "i64" => Ok(Self::I64),
+            "i128" => Ok(Self::I128),
+            "u32" => Ok(Self::U32),
+            "u64" => Ok(Self::U64),
+            "u128" => Ok(Self::U128),
+            _ => Err(format!(
+                "Unknown element type <{value}>, expected one of: {}",
+                Self::NAMES.join(", ")
+            )),
+        }
+    }
+}
+
+pub trait Render {
+    fn render(&self) -> Result<String, TreeError>;
+
+    fn describe(&self) -> Result<String, TreeError> {
+        self.render()
+    }
+}
+
+macro_rules! impl_render {
+    ($($ty:ty),*) => {
+        $(
+            impl Render for $ty {
+                fn render(&self) -> Result<String, TreeError> {
+                    Ok(self.to_string())
+                }
+            }
+        )*
+    };
+}
+
+impl_render!(i32, i64, i128, u32, u64, u128);
+
+impl<T: Display> Render for Checked<T> {
+    fn render(&self) -> Result<String, TreeError> {
+        self.get()
+            .map(|value| value.to_string())
+            .ok_or(TreeError::Overflow)
+    }
+}
+
+impl<T> Render for MaxSliceSum<Checked<T>>
+where
+    T: Ord + CheckedAdd + Default + Copy + Display,
+{
+    fn render(&self) -> Result<String, TreeError> {
+        self.answer().render()
+    }
+
+    fn describe(&self) -> Result<String, TreeError> {
+        Ok(format!(
+            "best_sum:    {}\ntotal_sum:   {}\nbest_prefix: {}\nbest_suffix: {}",
+            self.answer().render()?,
+            self.total_sum().render()?,
+            self.best_prefix().render()?,
+            self.best_suffix().render()?
+        ))
+    }
+}
+
+pub trait DynTree: Send + Sync {
+    fn get(&self, left: usize, right: usize) -> Result<String, TreeError>;
+
+    fn describe(&self, left: usize, right: usize) -> Result<String, TreeError>;
+
+    /// Replaces the value at `pos` and returns the previous one.
+    fn modify(&mut self, pos: usize, value: &str) -> Result<String, TreeError>;
+
+    fn values(&self) -> Vec<String>;
+
+    fn len(&self) -> usize;
+}
+
+struct TypedTree<C>
+where
+    C: SegmentTreeComputation,
+{
+    tree: SegmentTree<C::Input, C::Output, C>,
+    values: Vec<C::Input>,
+}
+
+impl<C> TypedTree<C>
+where
+    C: SegmentTreeComputation + Send + Sync + 'static,
+    C::Input: FromStr + Display + Send + Sync,
+    C::Output: Render + Default + Copy + PartialEq + Send + Sync,
+{
+    fn build(values: &[&str]) -> Result<Box<dyn DynTree>, TreeError> {
+        let values = values
+            .iter()
+            .map(|value| parse_value(value))
+            .collect::<Result<Vec<C::Input>, _>>()?;
+
+        Ok(Box::new(Self {
+            tree: SegmentTree::build(&values),
+            values,
+        }))
+    }
+}
+
+impl<C> DynTree for TypedTree<C>
+where
+    C: SegmentTreeComputation + Send + Sync,
+    C::Input: FromStr + Display + Send + Sync,
+    C::Output: Render + Default + Copy + Send + Sync,
+{
+    fn get(&self, left: usize, right: usize) -> Result<String, TreeError> {
+        self.tree.get(left, right)?.render()
+    }
+
+    fn describe(&self, left: usize, right: usize) -> Result<String, TreeError> {
+        self.tree.get(left, right)?.describe()
+    }
+
+    fn modify(&mut self, pos: usize, value: &str) -> Result<String, TreeError> {
+        let value = parse_value(value)?;
+        self.tree.modify(pos, &value)?;
+
+        Ok(std::mem::replace(&mut self.values[pos], value).to_string())
+    }
+
+    fn values(&self) -> Vec<String> {
+        self.values.iter().map(|value| value.to_string()).collect()
+    }
+
+    fn len(&self) -> usize {
+        self.tree.len()
+    }
+}
+
+fn parse_value<T: FromStr>(value: &str) -> Result<T, TreeError> {
+    value
+        .parse()
+        .map_err(|_| TreeError::InvalidValue(value.to_string()))
+}
+
+macro_rules! with_element_type {
+    ($element_type:expr, $t:ident => $body:expr) => {
+        match $element_type {
+            ElementType::I32 => {
+                type $t = i32;
+                $body
+            }
+            ElementType::I64 => {
+                type $t = i64;
+                $body
+            }
+            ElementType::I128 => {
+                type $t = i128;
+                $body
+            }
+            ElementType::U32 => {
+                type $t = u32;
+                $body
+            }
+            ElementType::U64 => {
+                type $t = u64;
+                $body
+            }
+            ElementType::U128 => {
+                type $t = u128;
+                $body
+            }
+        }
+    };
+}
+
+pub fn build_tree(
+    computation: Computation,
+    element_type: ElementType,
+    values: &[&str],
+) -> Result<Box<dyn DynTree>, TreeError> {
+    // Sums can overflow, so they run over `Checked` elements and report it.
+    with_element_type!(element_type, T => match computation {
+        Computation::Sum => TypedTree::<SumComputation<Checked<T>>>::build(values),
+        Computation::Max => TypedTree::<MaxComputation<T>>::build(values),
+        Computation::Min => TypedTree::<MinComputation<T>>::build(values),
+        Computation::MaxSliceSum => {
+            TypedTree::<MaxSliceSumComputation<Checked<T>>>::build(values)
+        }
+        Computation::Xor => TypedTree::<XorComputation<T>>::build(values),
+        Computation::Gcd => TypedTree::<GcdComputation<T>>::build(values),
+        Computation::BitAnd => TypedTree::<BitAndComputation<T>>::build(values),
+        Computation::BitOr => TypedTree::<BitOrComputation<T>>::build(values),
+    })
+}
@@ -0,0 +1,76 @@
+use core::{marker::PhantomData, ops::BitAnd};
+
+use super::{IdempotentComputation, SegmentTreeComputation};
+
+pub struct BitAndComputation<T> {
+    phantom: PhantomData<T>,
+}
+
+impl<T> SegmentTreeComputation for BitAndComputation<T>
+where
+    T: BitAnd<Output = T> + Clone,
+{
+    type Input = T;
+
+    type Output = T;
+
+    fn combine(left_result: &T, right_result: &T) -> T {
+        left_result.clone() & right_result.clone()
+    }
+
+    fn update(_: &T, new_value: &T) -> T {
+        Self::init(new_value)
+    }
+
+    fn init(value: &T) -> T {
+        value.clone()
+    }
+}
+
+impl<T> IdempotentComputation for BitAndComputation<T> where T: BitAnd<Output = T> + Clone {}
+
+#[cfg(test)]
+mod tests {
+    use super::BitAndComputation;
+    use crate::computation::SegmentTreeComputation;
+
+    #[test]
+    fn test_init() {
+        let tests = [0, 1, 12345, 5463455];
+
+        for value in tests {
+            let expected = value;
+            let actual = BitAndComputation::init(&value);
+
+            assert_eq!(expected, actual);
+        }
+    }
+
+    #[test]
+    fn test_update() {
+        let tests = [(353, 0), (5435, 1), (0, 12345), (645345, 5463455), (1, 1)];
+
+        for (prev, cur) in tests {
+            let expected = cur;
+            let actual = BitAndComputation::update(&prev, &cur);
+
+            assert_eq!(expected, actual);
+        }
+    }
+
+    #[test]
+    fn test_combine() {
+        let tests = [
+            (0b1100, 0b1010, 0b1000),
+            (5435, 0, 0),
+            (7, 7, 7),
+            (0xff, 0x0f, 0x0f),
+        ];
+
+        for (prev, cur, expected) in tests {
+            let actual = BitAndComputation::combine(&prev, &cur);
+
+            assert_eq!(expected, actual);
+        }
+    }
+}
@@ -0,0 +1,76 @@
+use core::{marker::PhantomData, ops::BitOr};
+
+use super::{IdempotentComputation, SegmentTreeComputation};
+
+pub struct BitOrComputation<T> {
+    phantom: PhantomData<T>,
+}
+
+impl<T> SegmentTreeComputation for BitOrComputation<T>
+where
+    T: BitOr<Output = T> + Clone,
+{
+    type Input = T;
+
+    type Output = T;
+
+    fn combine(left_result: &T, right_result: &T) -> T {
+        left_result.clone() | right_result.clone()
+    }
+
+    fn update(_: &T, new_value: &T) -> T {
+        Self::init(new_value)
+    }
+
+    fn init(value: &T) -> T {
+        value.clone()
+    }
+}
+
+impl<T> IdempotentComputation for BitOrComputation<T> where T: BitOr<Output = T> + Clone {}
+
+#[cfg(test)]
+mod tests {
+    use super::BitOrComputation;
+    use crate::computation::SegmentTreeComputation;
+
+    #[test]
+    fn test_init() {
+        let tests = [0, 1, 12345, 5463455];
+
+        for value in tests {
+            let expected = value;
+            let actual = BitOrComputation::init(&value);
+
+            assert_eq!(expected, actual);
+        }
+    }
+
+    #[test]
+    fn test_update() {
+        let tests = [(353, 0), (5435, 1), (0, 12345), (645345, 5463455), (1, 1)];
+
+        for (prev, cur) in tests {
+            let expected = cur;
+            let actual = BitOrComputation::update(&prev, &cur);
+
+            assert_eq!(expected, actual);
+        }
+    }
+
+    #[test]
+    fn test_combine() {
+        let tests = [
+            (0b1100, 0b1010, 0b1110),
+            (5435, 0, 5435),
+            (7, 7, 7),
+            (0xf0, 0x0f, 0xff),
+        ];
+
+        for (prev, cur, expected) in tests {
+            let actual = BitOrComputation::combine(&prev, &cur);
+
+            assert_eq!(expected, actual);
+        }
+    }
+}
@@ -0,0 +1,156 @@
+use super::SegmentTreeComputation;
+
+const OPEN_BRACKET: char = '(';
+const CLOSE_BRACKET: char = ')';
+
+pub struct BracketComputation;
+
+#[derive(Debug, PartialEq, Eq, Clone, Copy, Default)]
+pub struct BracketBalance {
+    unmatched_open: usize,
+    unmatched_close: usize,
+    matched_pairs: usize,
+}
+
+impl BracketBalance {
+    pub fn new(value: &char) -> Self {
+        match *value {
+            OPEN_BRACKET => Self {
+                unmatched_open: 1,
+                ..Self::default()
+            },
+            CLOSE_BRACKET => Self {
+                unmatched_close: 1,
+                ..Self::default()
+            },
+            _ => Self::default(),
+        }
+    }
+
+    pub fn from_slice(slice: &[char]) -> Self {
+        slice
+            .iter()
+            .map(Self::new)
+            .fold(Self::default(), |acc, cur| Self::concat(&acc, &cur))
+    }
+
+    pub fn unmatched_open(&self) -> usize {
+        self.unmatched_open
+    }
+
+    pub fn unmatched_close(&self) -> usize {
+        self.unmatched_close
+    }
+
+    pub fn matched_pairs(&self) -> usize {
+        self.matched_pairs
+    }
+
+    pub fn is_balanced(&self) -> bool {
+        self.unmatched_open == 0 && self.unmatched_close == 0
+    }
+
+    fn concat(left: &Self, right: &Self) -> Self {
+        let matched = left.unmatched_open.min(right.unmatched_close);
+
+        Self {
+            unmatched_open: left.unmatched_open - matched + right.unmatched_open,
+            unmatched_close: left.unmatched_close + right.unmatched_close - matched,
+            matched_pairs: left.matched_pairs + right.matched_pairs + matched,
+        }
+    }
+}
+
+impl SegmentTreeComputation for BracketComputation {
+    type Input = char;
+
+    type Output = BracketBalance;
+
+    fn combine(left_result: &Self::Output, right_result: &Self::Output) -> Self::Output {
+        BracketBalance::concat(left_result, right_result)
+    }
+
+    fn update(_: &Self::Output, new_value: &Self::Input) -> Self::Output {
+        Self::init(new_value)
+    }
+
+    fn init(value: &Self::Input) -> Self::Output {
+        Self::Output::new(value)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{BracketBalance, BracketComputation};
+    use crate::computation::SegmentTreeComputation;
+
+    #[test]
+    fn test_new_bracket_balance() {
+        let tests = [('(', 1, 0), (')', 0, 1), ('x', 0, 0), (' ', 0, 0)];
+
+        for (value, unmatched_open, unmatched_close) in tests {
+            let expected = BracketBalance {
+                unmatched_open,
+                unmatched_close,
+                matched_pairs: 0,
+            };
+            let actual = BracketBalance::new(&value);
+
+            assert_eq!(expected, actual);
+        }
+    }
+
+    #[test]
+    fn test_from_slice() {
+        let arr: Vec<char> = ")(a(())(".chars().collect();
+        let expected = BracketBalance {
+            unmatched_open: 2,
+            unmatched_close: 1,
+            matched_pairs: 2,
+        };
+        let actual = BracketBalance::from_slice(&arr);
+
+        assert_eq!(expected, actual);
+        assert!(!actual.is_balanced());
+        assert!(BracketBalance::from_slice(&arr[3..7]).is_balanced());
+    }
+
+    #[test]
+    fn test_init() {
+        let tests = ['(', ')', 'a'];
+
+        for value in tests {
+            let expected = BracketBalance::new(&value);
+            let actual = BracketComputation::init(&value);
+
+            assert_eq!(expected, actual);
+        }
+    }
+
+    #[test]
+    fn test_update() {
+        let tests = [('(', ')'), (')', '('), ('(', '('), ('x', ')')];
+
+        for (prev, cur) in tests {
+            let prev = BracketBalance::new(&prev);
+            let expected = BracketBalance::new(&cur);
+            let actual = BracketComputation::update(&prev, &cur);
+
+            assert_eq!(expected, actual);
+        }
+    }
+
+    #[test]
+    fn test_combine() {
+        let arr: Vec<char> = "(()))(()(()".chars().collect();
+
+        for mid in 1..arr.len() {
+            let left = BracketBalance::from_slice(&arr[..mid]);
+            let right = BracketBalance::from_slice(&arr[mid..]);
+            let expected = BracketBalance::from_slice(&arr);
+            let actual = BracketComputation::combine(&left, &right);
+
+            assert_eq!(expected, actual);
+        }
+    }
+}
@@ -0,0 +1,145 @@
+use core::marker::PhantomData;
+
+use super::{IdempotentComputation, SegmentTreeComputation};
+
+/// Integers with a greatest common divisor.
+///
+/// The result is never negative, so it doesn't depend on the signs or the
+/// order of the operands. The only exception is `gcd(MIN, 0)` and
+/// `gcd(MIN, MIN)` of a signed type, whose result `2^(BITS - 1)` doesn't fit
+/// and wraps to `MIN` like `wrapping_abs` does.
+pub trait GcdInteger: Copy {
+    fn gcd(self, other: Self) -> Self;
+}
+
+macro_rules! impl_gcd_integer {
+    ($($signed:ty => $unsigned:ty),*) => {
+        $(
+            impl GcdInteger for $unsigned {
+                fn gcd(mut self, mut other: Self) -> Self {
+                    while other != 0 {
+                        (self, other) = (other, self % other);
+                    }
+
+                    self
+                }
+            }
+
+            impl GcdInteger for $signed {
+                fn gcd(self, other: Self) -> Self {
+                    self.unsigned_abs().gcd(other.unsigned_abs()) as $signed
+                }
+            }
+        )*
+    };
+}
+
+impl_gcd_integer!(i8 => u8, i16 => u16, i32 => u32, i64 => u64, i128 => u128, isize => usize);
+
+pub struct GcdComputation<T> {
+    phantom: PhantomData<T>,
+}
+
+impl<T> SegmentTreeComputation for GcdComputation<T>
+where
+    T: GcdInteger,
+{
+    type Input = T;
+
+    type Output = T;
+
+    fn combine(left_result: &T, right_result: &T) -> T {
+        left_result.gcd(*right_result)
+    }
+
+    fn update(_: &T, new_value: &T) -> T {
+        Self::init(new_value)
+    }
+
+    fn init(value: &T) -> T {
+        value.gcd(*value)
+    }
+}
+
+impl<T> IdempotentComputation for GcdComputation<T> where T: GcdInteger {}
+
+#[cfg(test)]
+mod tests {
+    use super::GcdComputation;
+    use crate::computation::SegmentTreeComputation;
+
+    #[test]
+    fn test_init() {
+        let tests = [0, 1, 12345, 5463455];
+
+        for value in tests {
+            let expected = value;
+            let actual = GcdComputation::init(&value);
+
+            assert_eq!(expected, actual);
+        }
+    }
+
+    #[test]
+    fn test_update() {
+        let tests = [(353, 0), (5435, 1), (0, 12345), (645345, 5463455), (1, 1)];
+
+        for (prev, cur) in tests {
+            let expected = cur;
+            let actual = GcdComputation::update(&prev, &cur);
+
+            assert_eq!(expected, actual);
+        }
+    }
+
+    #[test]
+    fn test_combine() {
+        let tests = [
+            (353, 0, 353),
+            (0, 12345, 12345),
+            (12, 18, 6),
+            (18, 12, 6),
+            (17, 5, 1),
+            (1024, 768, 256),
+        ];
+
+        for (prev, cur, expected) in tests {
+            let actual = GcdComputation::combine(&prev, &cur);
+
+            assert_eq!(expected, actual);
+        }
+    }
+
+    #[test]
+    fn test_combine_negative() {
+        let tests = [
+            (-4i64, 6, 2),
+            (6, -4, 2),
+            (-12, -18, 6),
+            (-7, 0, 7),
+            (0, -7, 7),
+            (i64::MIN, -1, 1),
+            (-1, i64::MIN, 1),
+            (i64::MIN, 6, 2),
+            (i64::MIN, i64::MIN, i64::MIN),
+        ];
+
+        for (prev, cur, expected) in tests {
+            let actual = GcdComputation::combine(&prev, &cur);
+
+            assert_eq!(expected, actual, "gcd({prev}, {cur})");
+        }
+    }
+
+    #[test]
+    fn test_init_negative() {
+        let tests = [(-7i64, 7), (-1, 1), (12, 12), (i64::MIN, i64::MIN)];
+
+        for (value, expected) in tests {
+            let actual = GcdComputation::init(&value);
+
+            assert_eq!(expected, actual);
+            assert_eq!(actual, GcdComputation::combine(&actual, &actual));
+        }
+    }
+}
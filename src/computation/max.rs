@@ -1,6 +1,6 @@
-use std::{cmp::Ord, marker::PhantomData};
+use core::{cmp::Ord, marker::PhantomData};
 
-use super::SegmentTreeComputation;
+use super::{IdempotentComputation, SegmentTreeComputation};
 
 pub struct MaxComputation<T> {
     phantom: PhantomData<T>,
@@ -27,6 +27,8 @@ where
     }
 }
 
+impl<T> IdempotentComputation for MaxComputation<T> where T: Ord + Clone {}
+
 #[cfg(test)]
 mod tests {
     use crate::computation::{max::MaxComputation, SegmentTreeComputation};
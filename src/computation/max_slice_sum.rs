@@ -1,4 +1,4 @@
-use std::{marker::PhantomData, ops::Add};
+use core::{marker::PhantomData, ops::Add};
 
 use super::SegmentTreeComputation;
 
@@ -31,6 +31,18 @@ where
         &self.best_sum
     }
 
+    pub fn total_sum(&self) -> &T {
+        &self.total_sum
+    }
+
+    pub fn best_prefix(&self) -> &T {
+        &self.best_prefix
+    }
+
+    pub fn best_suffix(&self) -> &T {
+        &self.best_suffix
+    }
+
     pub fn from_slice(slice: &[T]) -> Self {
         let total_sum = slice
             .iter()
@@ -106,6 +118,7 @@ where
             .clone()
             .max(best_suffix.clone())
             .max(total_sum.clone())
+            .max(left_result.best_suffix.clone() + right_result.best_prefix.clone())
             .max(left_result.best_sum.clone())
             .max(right_result.best_sum.clone());
 
@@ -128,7 +141,10 @@ where
 
 #[cfg(test)]
 mod tests {
-    use crate::computation::{max_slice_sum::MaxSliceSumComputation, SegmentTreeComputation};
+    use crate::{
+        computation::{max_slice_sum::MaxSliceSumComputation, SegmentTreeComputation},
+        testing::laws::{check_laws, Laws},
+    };
 
     use super::MaxSliceSum;
 
@@ -163,6 +179,18 @@ mod tests {
         assert_eq!(expected, actual);
     }
 
+    #[test]
+    fn test_combine_crossing_midpoint() {
+        // The best slice [3, 4] takes the suffix of the left half and the
+        // prefix of the right half, and neither half covers it alone.
+        let left = MaxSliceSum::from_slice(&[-5, 3]);
+        let right = MaxSliceSum::from_slice(&[4, -6]);
+        let actual = MaxSliceSumComputation::combine(&left, &right);
+
+        assert_eq!(actual, MaxSliceSum::from_slice(&[-5, 3, 4, -6]));
+        assert_eq!(actual.best_sum, 7);
+    }
+
     #[test]
     fn test_init() {
         let tests = [0, 1, 12345, 5463455];
@@ -188,21 +216,27 @@ mod tests {
         }
     }
 
-    // TODO
-    // #[test]
-    // fn test_combine() {
-    //     let tests = [
-    //         (353, 0, 353),
-    //         (5435, 1, 5435),
-    //         (0, 12345, 12345),
-    //         (645345, 5463455, 5463455),
-    //         (1, 1, 1),
-    //     ];
+    #[test]
+    fn test_combine() {
+        let arr = [-2, 1, 2, 1, -10, 7, 2, -11, 4];
 
-    //     for (prev, cur, expected) in tests {
-    //         let actual = MaxComputation::combine(&prev, &cur);
+        for mid in 1..arr.len() {
+            let left = MaxSliceSum::from_slice(&arr[..mid]);
+            let right = MaxSliceSum::from_slice(&arr[mid..]);
+            let expected = MaxSliceSum::from_slice(&arr);
+            let actual = MaxSliceSumComputation::combine(&left, &right);
 
-    //         assert_eq!(expected, actual);
-    //     }
-    // }
+            assert_eq!(expected, actual);
+        }
+    }
+
+    #[test]
+    fn test_laws() {
+        let samples = [-7, -1, 0, 3, 12];
+
+        assert_eq!(
+            check_laws::<MaxSliceSumComputation<i64>>(&samples, &Laws::default()),
+            Ok(())
+        );
+    }
 }
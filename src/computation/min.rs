@@ -0,0 +1,76 @@
+use core::{cmp::Ord, marker::PhantomData};
+
+use super::{IdempotentComputation, SegmentTreeComputation};
+
+pub struct MinComputation<T> {
+    phantom: PhantomData<T>,
+}
+
+impl<T> SegmentTreeComputation for MinComputation<T>
+where
+    T: Ord + Clone,
+{
+    type Input = T;
+
+    type Output = T;
+
+    fn combine(left_result: &T, right_result: &T) -> T {
+        left_result.min(right_result).clone()
+    }
+
+    fn update(_: &T, new_value: &T) -> T {
+        Self::init(new_value)
+    }
+
+    fn init(value: &T) -> T {
+        value.clone()
+    }
+}
+
+impl<T> IdempotentComputation for MinComputation<T> where T: Ord + Clone {}
+
+#[cfg(test)]
+mod tests {
+    use crate::computation::{min::MinComputation, SegmentTreeComputation};
+
+    #[test]
+    fn test_init() {
+        let tests = [0, 1, 12345, 5463455];
+
+        for value in tests {
+            let expected = value;
+            let actual = MinComputation::init(&value);
+
+            assert_eq!(expected, actual);
+        }
+    }
+
+    #[test]
+    fn test_update() {
+        let tests = [(353, 0), (5435, 1), (0, 12345), (645345, 5463455), (1, 1)];
+
+        for (prev, cur) in tests {
+            let expected = cur;
+            let actual = MinComputation::update(&prev, &cur);
+
+            assert_eq!(expected, actual);
+        }
+    }
+
+    #[test]
+    fn test_combine() {
+        let tests = [
+            (353, 0, 0),
+            (5435, 1, 1),
+            (0, 12345, 0),
+            (645345, 5463455, 645345),
+            (1, 1, 1),
+        ];
+
+        for (prev, cur, expected) in tests {
+            let actual = MinComputation::combine(&prev, &cur);
+
+            assert_eq!(expected, actual);
+        }
+    }
+}
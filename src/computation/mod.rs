@@ -1,10 +1,26 @@
+pub use bit_and::BitAndComputation;
+pub use bit_or::BitOrComputation;
+pub use bracket::{BracketBalance, BracketComputation};
+pub use gcd::{GcdComputation, GcdInteger};
 pub use max::MaxComputation;
 pub use max_slice_sum::{MaxSliceSum, MaxSliceSumComputation};
+pub use min::MinComputation;
+pub use modular_product::ModularProductComputation;
+pub use rolling_hash::{RollingHash, RollingHashComputation};
 pub use sum::SumComputation;
+pub use xor::XorComputation;
 
+mod bit_and;
+mod bit_or;
+mod bracket;
+mod gcd;
 mod max;
 mod max_slice_sum;
+mod min;
+mod modular_product;
+mod rolling_hash;
 mod sum;
+mod xor;
 
 pub trait SegmentTreeComputation {
     type Input;
@@ -17,3 +33,36 @@ pub trait SegmentTreeComputation {
 
     fn init(value: &Self::Input) -> Self::Output;
 }
+
+/// A computation whose outputs form a commutative group under `combine`.
+pub trait InvertibleComputation: SegmentTreeComputation {
+    fn identity() -> Self::Output;
+
+    fn inverse(value: &Self::Output) -> Self::Output;
+}
+
+/// A computation where `combine(x, x) == x`, so overlapping ranges can be
+/// combined without changing the result.
+pub trait IdempotentComputation: SegmentTreeComputation {}
+
+/// Appends `value` to an optional partial result of a range on its left.
+pub(crate) fn append<C>(result: Option<C::Output>, value: C::Output) -> C::Output
+where
+    C: SegmentTreeComputation,
+{
+    match result {
+        Some(result) => C::combine(&result, &value),
+        None => value,
+    }
+}
+
+/// Prepends `value` to an optional partial result of a range on its right.
+pub(crate) fn prepend<C>(value: C::Output, result: Option<C::Output>) -> C::Output
+where
+    C: SegmentTreeComputation,
+{
+    match result {
+        Some(result) => C::combine(&value, &result),
+        None => value,
+    }
+}
@@ -0,0 +1,187 @@
+use super::{InvertibleComputation, SegmentTreeComputation};
+
+/// Product of values modulo `MODULUS`. Inverses require a prime `MODULUS`,
+/// which is checked at compile time, and only exist for values that are
+/// non-zero modulo `MODULUS`.
+pub struct ModularProductComputation<const MODULUS: u64>;
+
+impl<const MODULUS: u64> ModularProductComputation<MODULUS> {
+    const NON_ZERO_MODULUS: () = assert!(MODULUS > 0, "MODULUS must be positive");
+
+    const PRIME_MODULUS: () = assert!(is_prime(MODULUS), "MODULUS must be prime");
+
+    pub(crate) fn is_zero(value: &u64) -> bool {
+        Self::init(value) == 0
+    }
+
+    fn multiply(left: u64, right: u64) -> u64 {
+        multiply_mod(left, right, MODULUS)
+    }
+}
+
+impl<const MODULUS: u64> SegmentTreeComputation for ModularProductComputation<MODULUS> {
+    type Input = u64;
+
+    type Output = u64;
+
+    fn combine(left_result: &u64, right_result: &u64) -> u64 {
+        Self::multiply(*left_result, *right_result)
+    }
+
+    fn update(_: &u64, new_value: &u64) -> u64 {
+        Self::init(new_value)
+    }
+
+    fn init(value: &u64) -> u64 {
+        let () = Self::NON_ZERO_MODULUS;
+
+        value % MODULUS
+    }
+}
+
+impl<const MODULUS: u64> InvertibleComputation for ModularProductComputation<MODULUS> {
+    fn identity() -> u64 {
+        1 % MODULUS
+    }
+
+    fn inverse(value: &u64) -> u64 {
+        let () = Self::PRIME_MODULUS;
+
+        pow_mod(*value, MODULUS - 2, MODULUS)
+    }
+}
+
+const fn multiply_mod(left: u64, right: u64, modulus: u64) -> u64 {
+    (left as u128 * right as u128 % modulus as u128) as u64
+}
+
+const fn pow_mod(mut base: u64, mut exponent: u64, modulus: u64) -> u64 {
+    let mut result = 1 % modulus;
+
+    while exponent > 0 {
+        if exponent & 1 == 1 {
+            result = multiply_mod(result, base, modulus);
+        }
+        base = multiply_mod(base, base, modulus);
+        exponent >>= 1;
+    }
+
+    result
+}
+
+/// Deterministic Miller-Rabin; these bases are sufficient for every `u64`.
+const fn is_prime(value: u64) -> bool {
+    const BASES: [u64; 12] = [2, 3, 5, 7, 11, 13, 17, 19, 23, 29, 31, 37];
+
+    if value < 2 {
+        return false;
+    }
+
+    if value & 1 == 0 {
+        return value == 2;
+    }
+
+    let shift = (value - 1).trailing_zeros();
+    let odd = (value - 1) >> shift;
+
+    let mut index = 0;
+    while index < BASES.len() {
+        if value == BASES[index] {
+            return true;
+        }
+
+        let mut current = pow_mod(BASES[index], odd, value);
+        let mut round = 1;
+
+        if current != 1 && current != value - 1 {
+            while round < shift && current != value - 1 {
+                current = multiply_mod(current, current, value);
+                round += 1;
+            }
+            if current != value - 1 {
+                return false;
+            }
+        }
+        index += 1;
+    }
+
+    true
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{is_prime, ModularProductComputation};
+    use crate::computation::{InvertibleComputation, SegmentTreeComputation};
+
+    type Computation = ModularProductComputation<1_000_000_007>;
+
+    #[test]
+    fn test_init() {
+        let tests = [(0, 0), (1, 1), (12345, 12345), (1_000_000_008, 1)];
+
+        for (value, expected) in tests {
+            let actual = Computation::init(&value);
+
+            assert_eq!(expected, actual);
+        }
+    }
+
+    #[test]
+    fn test_update() {
+        let tests = [(353, 0), (5435, 1), (0, 12345), (645345, 5463455), (1, 1)];
+
+        for (prev, cur) in tests {
+            let expected = cur;
+            let actual = Computation::update(&prev, &cur);
+
+            assert_eq!(expected, actual);
+        }
+    }
+
+    #[test]
+    fn test_combine() {
+        let tests = [
+            (353, 0, 0),
+            (5435, 1, 5435),
+            (12, 12345, 148140),
+            (1_000_000_006, 1_000_000_006, 1),
+            (u32::MAX as u64, 2, 589934534),
+        ];
+
+        for (prev, cur, expected) in tests {
+            let actual = Computation::combine(&prev, &cur);
+
+            assert_eq!(expected, actual);
+        }
+    }
+
+    #[test]
+    fn test_inverse() {
+        let tests = [1, 2, 12345, 5463455, 1_000_000_006];
+
+        for value in tests {
+            let inverse = Computation::inverse(&value);
+            let actual = Computation::combine(&value, &inverse);
+
+            assert_eq!(Computation::identity(), actual);
+        }
+    }
+
+    #[test]
+    fn test_is_prime() {
+        let primes = [2, 3, 37, 41, 998_244_353, 1_000_000_007, u64::MAX - 58];
+        let composites = [0, 1, 4, 9, 25, 561, 1_000_000_005, 3_215_031_751, u64::MAX];
+
+        for value in primes {
+            assert!(is_prime(value), "{value}");
+        }
+        for value in composites {
+            assert!(!is_prime(value), "{value}");
+        }
+        for value in 0..2000u64 {
+            let expected = value >= 2 && (2..value).all(|divisor| value % divisor != 0);
+
+            assert_eq!(is_prime(value), expected, "{value}");
+        }
+    }
+}
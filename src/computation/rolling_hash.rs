@@ -0,0 +1,170 @@
+use core::marker::PhantomData;
+
+use super::SegmentTreeComputation;
+
+const FIRST_MODULUS: u64 = 1_000_000_007;
+const SECOND_MODULUS: u64 = 998_244_353;
+const FIRST_BASE: u64 = 911_382_323;
+const SECOND_BASE: u64 = 972_663_749;
+
+pub struct RollingHashComputation<T> {
+    phantom: PhantomData<T>,
+}
+
+#[derive(Debug, PartialEq, Eq, Clone, Copy, Default)]
+pub struct RollingHash {
+    first: u64,
+    second: u64,
+    first_power: u64,
+    second_power: u64,
+    len: usize,
+}
+
+impl RollingHash {
+    pub fn new<T>(value: &T) -> Self
+    where
+        T: Copy + Into<u64>,
+    {
+        let value: u64 = (*value).into();
+
+        Self {
+            first: value % FIRST_MODULUS,
+            second: value % SECOND_MODULUS,
+            first_power: FIRST_BASE,
+            second_power: SECOND_BASE,
+            len: 1,
+        }
+    }
+
+    pub fn from_slice<T>(slice: &[T]) -> Self
+    where
+        T: Copy + Into<u64>,
+    {
+        slice
+            .iter()
+            .map(Self::new)
+            .reduce(|acc, cur| Self::concat(&acc, &cur))
+            .expect("Slice is not empty")
+    }
+
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    fn concat(left: &Self, right: &Self) -> Self {
+        Self {
+            first: (left.first * right.first_power + right.first) % FIRST_MODULUS,
+            second: (left.second * right.second_power + right.second) % SECOND_MODULUS,
+            first_power: left.first_power * right.first_power % FIRST_MODULUS,
+            second_power: left.second_power * right.second_power % SECOND_MODULUS,
+            len: left.len + right.len,
+        }
+    }
+}
+
+impl<T> SegmentTreeComputation for RollingHashComputation<T>
+where
+    T: Copy + Into<u64>,
+{
+    type Input = T;
+
+    type Output = RollingHash;
+
+    fn combine(left_result: &Self::Output, right_result: &Self::Output) -> Self::Output {
+        RollingHash::concat(left_result, right_result)
+    }
+
+    fn update(_: &Self::Output, new_value: &Self::Input) -> Self::Output {
+        Self::init(new_value)
+    }
+
+    fn init(value: &Self::Input) -> Self::Output {
+        Self::Output::new(value)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{RollingHash, RollingHashComputation, FIRST_BASE, SECOND_BASE};
+    use crate::computation::SegmentTreeComputation;
+
+    #[test]
+    fn test_new_rolling_hash() {
+        let tests = [b'a', b'z', b'0', 0, 255];
+
+        for value in tests {
+            let expected = RollingHash {
+                first: value as u64,
+                second: value as u64,
+                first_power: FIRST_BASE,
+                second_power: SECOND_BASE,
+                len: 1,
+            };
+            let actual = RollingHash::new(&value);
+
+            assert_eq!(expected, actual);
+        }
+    }
+
+    #[test]
+    fn test_from_slice() {
+        let arr = b"abacaba";
+
+        assert_eq!(RollingHash::from_slice(arr).len(), arr.len());
+        assert_eq!(
+            RollingHash::from_slice(&arr[0..3]),
+            RollingHash::from_slice(&arr[4..7])
+        );
+        assert_ne!(
+            RollingHash::from_slice(&arr[0..3]),
+            RollingHash::from_slice(&arr[1..4])
+        );
+        assert_ne!(
+            RollingHash::from_slice(&[0u8, 1]),
+            RollingHash::from_slice(&[1u8])
+        );
+    }
+
+    #[test]
+    fn test_init() {
+        let tests = ['a', 'ї', '€', '🦀'];
+
+        for value in tests {
+            let expected = RollingHash::new(&value);
+            let actual = RollingHashComputation::init(&value);
+
+            assert_eq!(expected, actual);
+        }
+    }
+
+    #[test]
+    fn test_update() {
+        let tests = [(b'a', b'b'), (b'x', b'x'), (0, 255)];
+
+        for (prev, cur) in tests {
+            let prev = RollingHash::new(&prev);
+            let expected = RollingHash::new(&cur);
+            let actual = RollingHashComputation::update(&prev, &cur);
+
+            assert_eq!(expected, actual);
+        }
+    }
+
+    #[test]
+    fn test_combine() {
+        let arr = b"abracadabra";
+
+        for mid in 1..arr.len() {
+            let left = RollingHash::from_slice(&arr[..mid]);
+            let right = RollingHash::from_slice(&arr[mid..]);
+            let expected = RollingHash::from_slice(arr);
+            let actual = RollingHashComputation::<u8>::combine(&left, &right);
+
+            assert_eq!(expected, actual);
+        }
+    }
+}
@@ -1,5 +1,8 @@
-use super::SegmentTreeComputation;
-use std::{marker::PhantomData, ops::Add};
+use super::{InvertibleComputation, SegmentTreeComputation};
+use core::{
+    marker::PhantomData,
+    ops::{Add, Neg},
+};
 
 pub struct SumComputation<T> {
     phantom: PhantomData<T>,
@@ -26,10 +29,23 @@ where
     }
 }
 
+impl<T> InvertibleComputation for SumComputation<T>
+where
+    T: Add<Output = T> + Neg<Output = T> + Default + Clone,
+{
+    fn identity() -> T {
+        T::default()
+    }
+
+    fn inverse(value: &T) -> T {
+        -value.clone()
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::SumComputation;
-    use crate::computation::SegmentTreeComputation;
+    use crate::computation::{InvertibleComputation, SegmentTreeComputation};
 
     #[test]
     fn test_init() {
@@ -71,4 +87,16 @@ mod tests {
             assert_eq!(expected, actual);
         }
     }
+
+    #[test]
+    fn test_inverse() {
+        let tests = [0i64, 1, -12345, 5463455];
+
+        for value in tests {
+            let inverse = SumComputation::inverse(&value);
+            let actual = SumComputation::combine(&value, &inverse);
+
+            assert_eq!(SumComputation::<i64>::identity(), actual);
+        }
+    }
 }
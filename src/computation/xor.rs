@@ -0,0 +1,100 @@
+use core::{marker::PhantomData, ops::BitXor};
+
+use super::{InvertibleComputation, SegmentTreeComputation};
+
+pub struct XorComputation<T> {
+    phantom: PhantomData<T>,
+}
+
+impl<T> SegmentTreeComputation for XorComputation<T>
+where
+    T: BitXor<Output = T> + Clone,
+{
+    type Input = T;
+
+    type Output = T;
+
+    fn combine(left_result: &T, right_result: &T) -> T {
+        left_result.clone() ^ right_result.clone()
+    }
+
+    fn update(_: &T, new_value: &T) -> T {
+        Self::init(new_value)
+    }
+
+    fn init(value: &T) -> T {
+        value.clone()
+    }
+}
+
+impl<T> InvertibleComputation for XorComputation<T>
+where
+    T: BitXor<Output = T> + Default + Clone,
+{
+    fn identity() -> T {
+        T::default()
+    }
+
+    fn inverse(value: &T) -> T {
+        value.clone()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::XorComputation;
+    use crate::computation::{InvertibleComputation, SegmentTreeComputation};
+
+    #[test]
+    fn test_init() {
+        let tests = [0, 1, 12345, 5463455];
+
+        for value in tests {
+            let expected = value;
+            let actual = XorComputation::init(&value);
+
+            assert_eq!(expected, actual);
+        }
+    }
+
+    #[test]
+    fn test_update() {
+        let tests = [(353, 0), (5435, 1), (0, 12345), (645345, 5463455), (1, 1)];
+
+        for (prev, cur) in tests {
+            let expected = cur;
+            let actual = XorComputation::update(&prev, &cur);
+
+            assert_eq!(expected, actual);
+        }
+    }
+
+    #[test]
+    fn test_combine() {
+        let tests = [
+            (353, 0, 353),
+            (5, 3, 6),
+            (0, 12345, 12345),
+            (0b1010, 0b0110, 0b1100),
+            (1, 1, 0),
+        ];
+
+        for (prev, cur, expected) in tests {
+            let actual = XorComputation::combine(&prev, &cur);
+
+            assert_eq!(expected, actual);
+        }
+    }
+
+    #[test]
+    fn test_inverse() {
+        let tests = [0u32, 1, 12345, 5463455];
+
+        for value in tests {
+            let inverse = XorComputation::inverse(&value);
+            let actual = XorComputation::combine(&value, &inverse);
+
+            assert_eq!(XorComputation::<u32>::identity(), actual);
+        }
+    }
+}
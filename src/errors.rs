@@ -1,11 +1,63 @@
-use thiserror::Error;
+use core::fmt::{self, Display};
 
-#[derive(Error, Debug, PartialEq, Eq, Clone, Copy)]
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
 pub enum SegmentTreeError {
-    #[error("Index {index} is out of bounds. It should be smaller or equal to {len}")]
-    OutOfBounds { index: usize, len: usize },
-    #[error("Left index <{left}> should be lower or equal to the right index <{right}>")]
-    InvalidRange { left: usize, right: usize },
+    OutOfBounds {
+        index: usize,
+        len: usize,
+    },
+    InvalidRange {
+        left: usize,
+        right: usize,
+    },
+    InvalidCheckpoint,
+    InvalidNode {
+        index: usize,
+        left: usize,
+        right: usize,
+    },
+    CapacityExceeded {
+        len: usize,
+        capacity: usize,
+    },
+    LengthMismatch {
+        left: usize,
+        right: usize,
+    },
 }
 
+impl Display for SegmentTreeError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::OutOfBounds { index, len } => write!(
+                f,
+                "Index {index} is out of bounds. It should be smaller or equal to {len}"
+            ),
+            Self::InvalidRange { left, right } => write!(
+                f,
+                "Left index <{left}> should be lower or equal to the right index <{right}>"
+            ),
+            Self::InvalidCheckpoint => write!(
+                f,
+                "Checkpoint is no longer valid, it was committed or rolled back past"
+            ),
+            Self::InvalidNode { index, left, right } => write!(
+                f,
+                "Node {index} covering [{left}, {right}] does not match the combination of its children"
+            ),
+            Self::CapacityExceeded { len, capacity } => write!(
+                f,
+                "Cannot store {len} elements in a tree with capacity {capacity}"
+            ),
+            Self::LengthMismatch { left, right } => write!(
+                f,
+                "Cannot merge a tree over {left} positions with a tree over {right} positions"
+            ),
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for SegmentTreeError {}
+
 pub type SegmentTreeResult<T> = Result<T, SegmentTreeError>;
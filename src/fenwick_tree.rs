@@ -0,0 +1,539 @@
+use crate::{
+    computation::{
+        InvertibleComputation, ModularProductComputation, SumComputation, XorComputation,
+    },
+    errors::{SegmentTreeError, SegmentTreeResult},
+};
+use alloc::{vec, vec::Vec};
+use core::marker::PhantomData;
+
+pub struct FenwickTree<I, O, C>
+where
+    C: InvertibleComputation,
+{
+    data: Vec<O>,
+    len: usize,
+    phantom: PhantomData<(C, I)>,
+}
+
+pub type SumFenwickTree<T> = FenwickTree<T, T, SumComputation<T>>;
+pub type XorFenwickTree<T> = FenwickTree<T, T, XorComputation<T>>;
+
+/// Modular products with point updates. Zero has no modular inverse, so zeros
+/// are counted separately and the product only covers the non-zero values.
+pub struct ModularProductFenwickTree<const MODULUS: u64> {
+    products: FenwickTree<u64, u64, ModularProductComputation<MODULUS>>,
+    zeros: SumFenwickTree<i64>,
+}
+
+impl<I, O, C> FenwickTree<I, O, C>
+where
+    O: Clone,
+    C: InvertibleComputation<Input = I, Output = O>,
+{
+    pub fn build(arr: &[I]) -> Self {
+        Self::from_outputs(arr.iter().map(C::init).collect())
+    }
+
+    pub fn get(&self, left: usize, right: usize) -> SegmentTreeResult<O> {
+        self.check_range(left, right)?;
+
+        let result = self.prefix(right + 1);
+        if left == 0 {
+            Ok(result)
+        } else {
+            Ok(C::combine(&result, &C::inverse(&self.prefix(left))))
+        }
+    }
+
+    pub fn modify(&mut self, pos: usize, value: &I) -> SegmentTreeResult<()> {
+        let prev_value = self.get(pos, pos)?;
+        let delta = C::combine(&C::update(&prev_value, value), &C::inverse(&prev_value));
+
+        self.internal_add(pos + 1, &delta);
+        Ok(())
+    }
+
+    pub fn add(&mut self, pos: usize, delta: &O) -> SegmentTreeResult<()> {
+        if pos >= self.len {
+            Err(SegmentTreeError::OutOfBounds {
+                index: pos,
+                len: self.len,
+            })
+        } else {
+            self.internal_add(pos + 1, delta);
+            Ok(())
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    fn from_outputs(values: Vec<O>) -> Self {
+        let len = values.len();
+        let mut data = Vec::with_capacity(len + 1);
+        data.push(C::identity());
+        data.extend(values);
+
+        for index in 1..=len {
+            let parent = index + lowest_bit(index);
+            if parent <= len {
+                data[parent] = C::combine(&data[parent], &data[index]);
+            }
+        }
+
+        Self {
+            data,
+            len,
+            phantom: PhantomData,
+        }
+    }
+
+    fn check_range(&self, left: usize, right: usize) -> SegmentTreeResult<()> {
+        if left > right {
+            Err(SegmentTreeError::InvalidRange { left, right })
+        } else if right >= self.len {
+            Err(SegmentTreeError::OutOfBounds {
+                index: right,
+                len: self.len,
+            })
+        } else {
+            Ok(())
+        }
+    }
+
+    fn prefix(&self, mut index: usize) -> O {
+        let mut result = C::identity();
+
+        while index > 0 {
+            result = C::combine(&result, &self.data[index]);
+            index -= lowest_bit(index);
+        }
+
+        result
+    }
+
+    fn internal_add(&mut self, mut index: usize, delta: &O) {
+        while index <= self.len {
+            self.data[index] = C::combine(&self.data[index], delta);
+            index += lowest_bit(index);
+        }
+    }
+}
+
+pub struct RangeFenwickTree<I, O, C>
+where
+    C: InvertibleComputation,
+{
+    linear: FenwickTree<I, O, C>,
+    constant: FenwickTree<I, O, C>,
+}
+
+pub type SumRangeFenwickTree<T> = RangeFenwickTree<T, T, SumComputation<T>>;
+pub type XorRangeFenwickTree<T> = RangeFenwickTree<T, T, XorComputation<T>>;
+
+/// Modular products with range multiplication, counting zeros separately like
+/// [`ModularProductFenwickTree`].
+pub struct ModularProductRangeFenwickTree<const MODULUS: u64> {
+    products: RangeFenwickTree<u64, u64, ModularProductComputation<MODULUS>>,
+    zeros: SumRangeFenwickTree<i64>,
+}
+
+impl<I, O, C> RangeFenwickTree<I, O, C>
+where
+    O: Clone,
+    C: InvertibleComputation<Input = I, Output = O>,
+{
+    pub fn build(arr: &[I]) -> Self {
+        let inverted = arr.iter().map(|value| C::inverse(&C::init(value)));
+
+        Self {
+            linear: FenwickTree::from_outputs(vec![C::identity(); arr.len()]),
+            constant: FenwickTree::from_outputs(inverted.collect()),
+        }
+    }
+
+    pub fn get(&self, left: usize, right: usize) -> SegmentTreeResult<O> {
+        self.linear.check_range(left, right)?;
+
+        let result = self.prefix(right + 1);
+        if left == 0 {
+            Ok(result)
+        } else {
+            Ok(C::combine(&result, &C::inverse(&self.prefix(left))))
+        }
+    }
+
+    pub fn modify(&mut self, pos: usize, value: &I) -> SegmentTreeResult<()> {
+        let prev_value = self.get(pos, pos)?;
+        let delta = C::combine(&C::update(&prev_value, value), &C::inverse(&prev_value));
+
+        self.add(pos, pos, &delta)
+    }
+
+    pub fn add(&mut self, left: usize, right: usize, delta: &O) -> SegmentTreeResult<()> {
+        self.linear.check_range(left, right)?;
+
+        let (left, right) = (left + 1, right + 1);
+        self.linear.internal_add(left, delta);
+        self.linear.internal_add(right + 1, &C::inverse(delta));
+        self.constant
+            .internal_add(left, &repeat::<C>(delta, left - 1));
+        self.constant
+            .internal_add(right + 1, &C::inverse(&repeat::<C>(delta, right)));
+
+        Ok(())
+    }
+
+    pub fn len(&self) -> usize {
+        self.linear.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.linear.is_empty()
+    }
+
+    fn prefix(&self, index: usize) -> O {
+        let linear = repeat::<C>(&self.linear.prefix(index), index);
+        let constant = C::inverse(&self.constant.prefix(index));
+
+        C::combine(&linear, &constant)
+    }
+}
+
+impl<const MODULUS: u64> ModularProductFenwickTree<MODULUS> {
+    pub fn build(arr: &[u64]) -> Self {
+        let (products, zeros) = split_zeros::<MODULUS>(arr);
+
+        Self {
+            products: FenwickTree::build(&products),
+            zeros: FenwickTree::build(&zeros),
+        }
+    }
+
+    pub fn get(&self, left: usize, right: usize) -> SegmentTreeResult<u64> {
+        if self.zeros.get(left, right)? > 0 {
+            Ok(0)
+        } else {
+            self.products.get(left, right)
+        }
+    }
+
+    pub fn modify(&mut self, pos: usize, value: &u64) -> SegmentTreeResult<()> {
+        let (product, zero) = split_zero::<MODULUS>(value);
+        self.zeros.modify(pos, &zero)?;
+
+        self.products.modify(pos, &product)
+    }
+
+    /// Multiplies the value at `pos` by `factor`.
+    pub fn add(&mut self, pos: usize, factor: &u64) -> SegmentTreeResult<()> {
+        let (product, zero) = split_zero::<MODULUS>(factor);
+        self.zeros.add(pos, &zero)?;
+
+        self.products.add(pos, &product)
+    }
+
+    pub fn len(&self) -> usize {
+        self.products.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.products.is_empty()
+    }
+}
+
+impl<const MODULUS: u64> ModularProductRangeFenwickTree<MODULUS> {
+    pub fn build(arr: &[u64]) -> Self {
+        let (products, zeros) = split_zeros::<MODULUS>(arr);
+
+        Self {
+            products: RangeFenwickTree::build(&products),
+            zeros: RangeFenwickTree::build(&zeros),
+        }
+    }
+
+    pub fn get(&self, left: usize, right: usize) -> SegmentTreeResult<u64> {
+        if self.zeros.get(left, right)? > 0 {
+            Ok(0)
+        } else {
+            self.products.get(left, right)
+        }
+    }
+
+    pub fn modify(&mut self, pos: usize, value: &u64) -> SegmentTreeResult<()> {
+        let (product, zero) = split_zero::<MODULUS>(value);
+        self.zeros.modify(pos, &zero)?;
+
+        self.products.modify(pos, &product)
+    }
+
+    /// Multiplies every value in `left..=right` by `factor`.
+    pub fn add(&mut self, left: usize, right: usize, factor: &u64) -> SegmentTreeResult<()> {
+        let (product, zero) = split_zero::<MODULUS>(factor);
+        self.zeros.add(left, right, &zero)?;
+
+        self.products.add(left, right, &product)
+    }
+
+    pub fn len(&self) -> usize {
+        self.products.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.products.is_empty()
+    }
+}
+
+/// Splits a value into the factor kept in the product tree and its zero count.
+fn split_zero<const MODULUS: u64>(value: &u64) -> (u64, i64) {
+    if ModularProductComputation::<MODULUS>::is_zero(value) {
+        (1, 1)
+    } else {
+        (*value, 0)
+    }
+}
+
+fn split_zeros<const MODULUS: u64>(arr: &[u64]) -> (Vec<u64>, Vec<i64>) {
+    arr.iter().map(split_zero::<MODULUS>).unzip()
+}
+
+fn lowest_bit(index: usize) -> usize {
+    index & index.wrapping_neg()
+}
+
+fn repeat<C>(value: &C::Output, mut count: usize) -> C::Output
+where
+    C: InvertibleComputation,
+    C::Output: Clone,
+{
+    let mut result = C::identity();
+    let mut base = value.clone();
+
+    while count > 0 {
+        if count & 1 == 1 {
+            result = C::combine(&result, &base);
+        }
+        base = C::combine(&base, &base);
+        count >>= 1;
+    }
+
+    result
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{
+        ModularProductFenwickTree, ModularProductRangeFenwickTree, SumFenwickTree,
+        SumRangeFenwickTree, XorFenwickTree, XorRangeFenwickTree,
+    };
+    use crate::{SegmentTreeError, SegmentTreeResult};
+
+    const MODULUS: u64 = 1_000_000_007;
+
+    #[test]
+    fn test_build() {
+        let arr = [1, 3, 7, 27, 73, 5];
+        let tree = SumFenwickTree::build(&arr);
+
+        assert!(!tree.is_empty());
+        assert_eq!(tree.len(), arr.len());
+        assert_eq!(tree.data, vec![0, 1, 4, 7, 38, 73, 78]);
+    }
+
+    #[test]
+    fn test_empty_tree() {
+        let arr: [i64; 0] = [];
+        let mut tree = SumFenwickTree::build(&arr);
+        let mut range_tree = SumRangeFenwickTree::build(&arr);
+
+        assert!(tree.is_empty());
+        assert!(range_tree.is_empty());
+
+        for value in [0, 1, 2, 100] {
+            let expected = Err(SegmentTreeError::OutOfBounds {
+                index: value,
+                len: 0,
+            });
+
+            assert_eq!(tree.get(value, value), expected);
+            assert_eq!(tree.modify(value, &1), expected.map(|_| ()));
+            assert_eq!(range_tree.get(value, value), expected);
+            assert_eq!(range_tree.modify(value, &1), expected.map(|_| ()));
+        }
+    }
+
+    #[test]
+    fn test_get() {
+        let arr = [1, -3, 7, 27, -73, 5, 0, 11];
+        let tree = SumFenwickTree::build(&arr);
+        let range_tree = SumRangeFenwickTree::build(&arr);
+
+        for left in 0..arr.len() {
+            for right in left..arr.len() {
+                let expected: SegmentTreeResult<i32> = Ok(arr[left..=right].iter().sum());
+
+                assert_eq!(tree.get(left, right), expected);
+                assert_eq!(range_tree.get(left, right), expected);
+            }
+        }
+    }
+
+    #[test]
+    fn test_get_errors() {
+        let arr = [1, 3, 7, 27, 73];
+        let tree = SumFenwickTree::build(&arr);
+        let range_tree = SumRangeFenwickTree::build(&arr);
+
+        for (left, right) in [(0, 5), (1, 6), (6, 1234)] {
+            let expected = Err(SegmentTreeError::OutOfBounds {
+                index: right,
+                len: 5,
+            });
+
+            assert_eq!(tree.get(left, right), expected);
+            assert_eq!(range_tree.get(left, right), expected);
+        }
+
+        for (left, right) in [(5, 4), (2, 1), (4, 1)] {
+            let expected = Err(SegmentTreeError::InvalidRange { left, right });
+
+            assert_eq!(tree.get(left, right), expected);
+            assert_eq!(range_tree.get(left, right), expected);
+        }
+    }
+
+    #[test]
+    fn test_modify() {
+        let mut arr = [5u32, 3, 9, 12, 0, 7, 1];
+        let mut tree = XorFenwickTree::build(&arr);
+        let mut range_tree = XorRangeFenwickTree::build(&arr);
+
+        for (pos, value) in [(3, 6), (0, 15), (6, 2), (3, 3)] {
+            arr[pos] = value;
+            assert_eq!(tree.modify(pos, &value), Ok(()));
+            assert_eq!(range_tree.modify(pos, &value), Ok(()));
+
+            for left in 0..arr.len() {
+                for right in left..arr.len() {
+                    let expected = arr[left..=right].iter().fold(0, |acc, cur| acc ^ cur);
+
+                    assert_eq!(tree.get(left, right), Ok(expected));
+                    assert_eq!(range_tree.get(left, right), Ok(expected));
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_modular_product() {
+        let mut arr = [2u64, 3, 1_000_000_006, 12345, 7];
+        let mut tree = ModularProductFenwickTree::<MODULUS>::build(&arr);
+
+        assert_eq!(tree.modify(1, &5), Ok(()));
+        arr[1] = 5;
+
+        for left in 0..arr.len() {
+            for right in left..arr.len() {
+                let expected = arr[left..=right]
+                    .iter()
+                    .fold(1, |acc, cur| acc * cur % MODULUS);
+
+                assert_eq!(tree.get(left, right), Ok(expected));
+            }
+        }
+    }
+
+    #[test]
+    fn test_modular_product_zeros() {
+        let mut arr = [0u64, 3, MODULUS, 5, 2];
+        let mut tree = ModularProductFenwickTree::<MODULUS>::build(&arr);
+        let mut range_tree = ModularProductRangeFenwickTree::<MODULUS>::build(&arr);
+
+        assert_eq!(tree.get(1, 1), Ok(3));
+        assert_eq!(range_tree.get(1, 1), Ok(3));
+
+        for (pos, value) in [(0, 7), (3, 0), (2, 4), (3, 1_000_000_006)] {
+            arr[pos] = value;
+            assert_eq!(tree.modify(pos, &value), Ok(()));
+            assert_eq!(range_tree.modify(pos, &value), Ok(()));
+
+            for left in 0..arr.len() {
+                for right in left..arr.len() {
+                    let expected = arr[left..=right]
+                        .iter()
+                        .fold(1, |acc, cur| acc * cur % MODULUS);
+
+                    assert_eq!(tree.get(left, right), Ok(expected));
+                    assert_eq!(range_tree.get(left, right), Ok(expected));
+                }
+            }
+        }
+
+        assert_eq!(tree.add(1, &0), Ok(()));
+        assert_eq!(tree.get(1, 1), Ok(0));
+        assert_eq!(tree.modify(1, &3), Ok(()));
+        assert_eq!(tree.get(0, 1), Ok(21));
+
+        assert_eq!(range_tree.add(1, 3, &0), Ok(()));
+        assert_eq!(range_tree.get(0, 0), Ok(7));
+        assert_eq!(range_tree.get(1, 4), Ok(0));
+        assert_eq!(range_tree.modify(2, &6), Ok(()));
+        assert_eq!(range_tree.get(2, 2), Ok(6));
+        assert_eq!(range_tree.get(2, 3), Ok(0));
+    }
+
+    #[test]
+    fn test_range_add() {
+        let mut arr = [4i64, -2, 0, 9, 13, -7, 1, 1, 8];
+        let mut range_tree = SumRangeFenwickTree::build(&arr);
+
+        for (left, right, delta) in [(0, 8, 3), (2, 5, -10), (4, 4, 7), (7, 8, 100)] {
+            arr[left..=right]
+                .iter_mut()
+                .for_each(|value| *value += delta);
+            assert_eq!(range_tree.add(left, right, &delta), Ok(()));
+
+            for left in 0..arr.len() {
+                for right in left..arr.len() {
+                    let expected = arr[left..=right].iter().sum();
+
+                    assert_eq!(range_tree.get(left, right), Ok(expected));
+                }
+            }
+        }
+
+        assert_eq!(
+            range_tree.add(3, 9, &1),
+            Err(SegmentTreeError::OutOfBounds { index: 9, len: 9 })
+        );
+        assert_eq!(
+            range_tree.add(3, 2, &1),
+            Err(SegmentTreeError::InvalidRange { left: 3, right: 2 })
+        );
+    }
+
+    #[test]
+    fn test_range_multiply() {
+        let mut arr = [2u64, 3, 5, 7, 11];
+        let mut range_tree = ModularProductRangeFenwickTree::<MODULUS>::build(&arr);
+
+        assert_eq!(range_tree.add(1, 3, &10), Ok(()));
+        arr[1..=3].iter_mut().for_each(|value| *value *= 10);
+
+        for left in 0..arr.len() {
+            for right in left..arr.len() {
+                let expected = arr[left..=right]
+                    .iter()
+                    .fold(1, |acc, cur| acc * cur % MODULUS);
+
+                assert_eq!(range_tree.get(left, right), Ok(expected));
+            }
+        }
+    }
+}
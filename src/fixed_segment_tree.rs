@@ -0,0 +1,158 @@
+use crate::{
+    array_segment_tree::ArraySegmentTree,
+    computation::{MaxComputation, SegmentTreeComputation, SumComputation},
+    errors::{SegmentTreeError, SegmentTreeResult},
+};
+use core::marker::PhantomData;
+
+/// Segment tree over at most `N` elements that keeps its nodes inline and
+/// never allocates.
+///
+/// It is an [`ArraySegmentTree`] over `N` slots whose slots past `len` hold
+/// `O::default()`. Queries stay within `len`, and the nodes they read never
+/// cover those slots.
+pub struct FixedSegmentTree<I, O, C, const N: usize>
+where
+    C: SegmentTreeComputation,
+{
+    tree: ArraySegmentTree<C, N>,
+    len: usize,
+    phantom: PhantomData<(I, O)>,
+}
+
+pub type SumFixedSegmentTree<T, const N: usize> = FixedSegmentTree<T, T, SumComputation<T>, N>;
+pub type MaxFixedSegmentTree<T, const N: usize> = FixedSegmentTree<T, T, MaxComputation<T>, N>;
+
+impl<I, O, C, const N: usize> FixedSegmentTree<I, O, C, N>
+where
+    O: Default + Copy,
+    C: SegmentTreeComputation<Input = I, Output = O>,
+{
+    pub fn build(arr: &[I]) -> SegmentTreeResult<Self> {
+        if arr.len() > N {
+            return Err(SegmentTreeError::CapacityExceeded {
+                len: arr.len(),
+                capacity: N,
+            });
+        }
+
+        let leaves = core::array::from_fn(|pos| arr.get(pos).map_or_else(O::default, C::init));
+
+        Ok(Self {
+            tree: ArraySegmentTree::from_leaves(leaves),
+            len: arr.len(),
+            phantom: PhantomData,
+        })
+    }
+
+    pub fn get(&self, left: usize, right: usize) -> SegmentTreeResult<O> {
+        if left > right {
+            Err(SegmentTreeError::InvalidRange { left, right })
+        } else if right >= self.len {
+            Err(SegmentTreeError::OutOfBounds {
+                index: right,
+                len: self.len,
+            })
+        } else {
+            self.tree.get(left, right)
+        }
+    }
+
+    pub fn modify(&mut self, pos: usize, value: &I) -> SegmentTreeResult<()> {
+        if pos >= self.len {
+            return Err(SegmentTreeError::OutOfBounds {
+                index: pos,
+                len: self.len,
+            });
+        }
+
+        self.tree.modify(pos, value)
+    }
+
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    pub const fn capacity(&self) -> usize {
+        N
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{FixedSegmentTree, MaxFixedSegmentTree, SumFixedSegmentTree};
+    use crate::{
+        computation::{RollingHash, RollingHashComputation},
+        SegmentTreeError,
+    };
+
+    #[test]
+    fn test_get_and_modify() {
+        let mut arr = [4i64, -7, 12, 0, 3, 3, -1, 25, 8, -9, 6];
+        let mut sum = SumFixedSegmentTree::<_, 16>::build(&arr).unwrap();
+        let mut max = MaxFixedSegmentTree::<_, 11>::build(&arr).unwrap();
+
+        for (pos, value) in [(0, 10), (10, -3), (5, 40), (7, 0)] {
+            arr[pos] = value;
+            assert_eq!(sum.modify(pos, &value), Ok(()));
+            assert_eq!(max.modify(pos, &value), Ok(()));
+
+            for left in 0..arr.len() {
+                for right in left..arr.len() {
+                    let slice = &arr[left..=right];
+
+                    assert_eq!(sum.get(left, right), Ok(slice.iter().sum()));
+                    assert_eq!(max.get(left, right), Ok(*slice.iter().max().unwrap()));
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_non_commutative_order() {
+        let text = b"abracadabra";
+        let tree = FixedSegmentTree::<_, _, RollingHashComputation<u8>, 12>::build(text).unwrap();
+
+        for left in 0..text.len() {
+            for right in left..text.len() {
+                let expected = RollingHash::from_slice(&text[left..=right]);
+
+                assert_eq!(tree.get(left, right), Ok(expected));
+            }
+        }
+    }
+
+    #[test]
+    fn test_capacity() {
+        assert_eq!(
+            SumFixedSegmentTree::<_, 2>::build(&[1, 2, 3]).err(),
+            Some(SegmentTreeError::CapacityExceeded {
+                len: 3,
+                capacity: 2
+            })
+        );
+
+        let mut tree = SumFixedSegmentTree::<i32, 8>::build(&[5, 6]).unwrap();
+        assert_eq!(tree.capacity(), 8);
+        assert_eq!(tree.len(), 2);
+        assert_eq!(
+            tree.get(0, 2),
+            Err(SegmentTreeError::OutOfBounds { index: 2, len: 2 })
+        );
+        assert_eq!(
+            tree.modify(2, &1),
+            Err(SegmentTreeError::OutOfBounds { index: 2, len: 2 })
+        );
+
+        let empty = SumFixedSegmentTree::<i32, 0>::build(&[]).unwrap();
+        assert!(empty.is_empty());
+        assert_eq!(
+            empty.get(0, 0),
+            Err(SegmentTreeError::OutOfBounds { index: 0, len: 0 })
+        );
+    }
+}
@@ -0,0 +1,565 @@
+use crate::{
+    computation::{append, MaxComputation, SegmentTreeComputation, SumComputation},
+    errors::{SegmentTreeError, SegmentTreeResult},
+};
+use alloc::boxed::Box;
+use core::{cmp::Ordering, marker::PhantomData};
+
+type Link<C> = Option<Box<Node<C>>>;
+
+struct Node<C>
+where
+    C: SegmentTreeComputation,
+{
+    value: C::Output,
+    aggregate: C::Output,
+    /// Aggregate of the subtree read right to left, so that reversing a range
+    /// only swaps two fields even for non-commutative computations.
+    reversed: C::Output,
+    size: usize,
+    priority: u64,
+    /// The subtree was reversed but its children were not swapped yet.
+    reverse: bool,
+    left: Link<C>,
+    right: Link<C>,
+}
+
+/// Sequence with range aggregates that, unlike `SegmentTree`, supports
+/// inserting, removing and reversing elements anywhere.
+///
+/// Elements are kept in a treap keyed by position: the key of a node is the
+/// size of everything to its left, so it is never stored. All operations take
+/// `O(log n)` expected time.
+///
+/// Priorities come from a splitmix64 generator owned by the treap, so the
+/// shape of a treap only depends on the operations applied to it.
+pub struct ImplicitTreap<I, O, C>
+where
+    C: SegmentTreeComputation,
+{
+    root: Link<C>,
+    state: u64,
+    phantom: PhantomData<(I, O)>,
+}
+
+const SEED: u64 = 0x5eed_1e55_c0ff_ee00;
+
+pub type SumImplicitTreap<T> = ImplicitTreap<T, T, SumComputation<T>>;
+pub type MaxImplicitTreap<T> = ImplicitTreap<T, T, MaxComputation<T>>;
+
+impl<I, O, C> ImplicitTreap<I, O, C>
+where
+    O: Copy,
+    C: SegmentTreeComputation<Input = I, Output = O>,
+{
+    pub fn new() -> Self {
+        Self::with_state(SEED)
+    }
+
+    pub fn build(arr: &[I]) -> Self {
+        let mut treap = Self::new();
+        let mut root = None;
+        for value in arr {
+            root = merge(root, Some(Node::new(value, treap.next_priority())));
+        }
+        treap.root = root;
+
+        treap
+    }
+
+    pub fn get(&self, left: usize, right: usize) -> SegmentTreeResult<O> {
+        if left > right {
+            return Err(SegmentTreeError::InvalidRange { left, right });
+        }
+
+        match &self.root {
+            Some(root) if right < root.size => Ok(fold(root, left, right, false)),
+            _ => Err(SegmentTreeError::OutOfBounds {
+                index: right,
+                len: self.len(),
+            }),
+        }
+    }
+
+    pub fn modify(&mut self, pos: usize, value: &I) -> SegmentTreeResult<()> {
+        self.check_pos(pos)?;
+
+        if let Some(root) = &mut self.root {
+            modify(root, pos, value);
+        }
+
+        Ok(())
+    }
+
+    pub fn insert(&mut self, pos: usize, value: &I) -> SegmentTreeResult<()> {
+        if pos > self.len() {
+            return Err(SegmentTreeError::OutOfBounds {
+                index: pos,
+                len: self.len(),
+            });
+        }
+
+        let node = Node::new(value, self.next_priority());
+        let (left, right) = split(self.root.take(), pos);
+        self.root = merge(merge(left, Some(node)), right);
+
+        Ok(())
+    }
+
+    /// Removes the element at `pos`, returning its output.
+    pub fn remove(&mut self, pos: usize) -> SegmentTreeResult<O> {
+        self.check_pos(pos)?;
+
+        let (left, rest) = split(self.root.take(), pos);
+        let (removed, right) = split(rest, 1);
+        self.root = merge(left, right);
+
+        Ok(removed.expect("The range holds one element").value)
+    }
+
+    /// Reverses the elements in `[left, right]`.
+    pub fn reverse(&mut self, left: usize, right: usize) -> SegmentTreeResult<()> {
+        if left > right {
+            return Err(SegmentTreeError::InvalidRange { left, right });
+        }
+        self.check_pos(right)?;
+
+        let (head, rest) = split(self.root.take(), left);
+        let (mut middle, tail) = split(rest, right - left + 1);
+        if let Some(middle) = &mut middle {
+            middle.toggle();
+        }
+        self.root = merge(merge(head, middle), tail);
+
+        Ok(())
+    }
+
+    /// Keeps the first `pos` elements and returns the rest as a new treap.
+    pub fn split(&mut self, pos: usize) -> SegmentTreeResult<Self> {
+        if pos > self.len() {
+            return Err(SegmentTreeError::OutOfBounds {
+                index: pos,
+                len: self.len(),
+            });
+        }
+
+        let (left, right) = split(self.root.take(), pos);
+        self.root = left;
+
+        // The new treap draws from its own stream instead of repeating ours.
+        let mut rest = Self::with_state(self.next_priority());
+        rest.root = right;
+
+        Ok(rest)
+    }
+
+    /// Appends all elements of `other` after the elements of `self`.
+    pub fn concat(&mut self, other: Self) {
+        self.root = merge(self.root.take(), other.root);
+        // Treaps built apart start from the same seed, keep later priorities
+        // independent from the ones `other` already handed out.
+        self.state ^= mix(other.state);
+    }
+
+    pub fn len(&self) -> usize {
+        size(&self.root)
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.root.is_none()
+    }
+
+    fn with_state(state: u64) -> Self {
+        Self {
+            root: None,
+            state,
+            phantom: PhantomData,
+        }
+    }
+
+    fn next_priority(&mut self) -> u64 {
+        self.state = self.state.wrapping_add(0x9e37_79b9_7f4a_7c15);
+        mix(self.state)
+    }
+
+    fn check_pos(&self, pos: usize) -> SegmentTreeResult<()> {
+        if pos >= self.len() {
+            Err(SegmentTreeError::OutOfBounds {
+                index: pos,
+                len: self.len(),
+            })
+        } else {
+            Ok(())
+        }
+    }
+}
+
+impl<I, O, C> Default for ImplicitTreap<I, O, C>
+where
+    O: Copy,
+    C: SegmentTreeComputation<Input = I, Output = O>,
+{
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<C> Node<C>
+where
+    C: SegmentTreeComputation,
+    C::Output: Copy,
+{
+    fn new(value: &C::Input, priority: u64) -> Box<Self> {
+        let value = C::init(value);
+
+        Box::new(Self {
+            value,
+            aggregate: value,
+            reversed: value,
+            size: 1,
+            priority,
+            reverse: false,
+            left: None,
+            right: None,
+        })
+    }
+
+    fn update(&mut self) {
+        self.size = 1 + size(&self.left) + size(&self.right);
+        self.aggregate = self.value;
+        self.reversed = self.value;
+
+        if let Some(left) = &self.left {
+            self.aggregate = C::combine(&left.aggregate, &self.aggregate);
+            self.reversed = C::combine(&self.reversed, &left.reversed);
+        }
+        if let Some(right) = &self.right {
+            self.aggregate = C::combine(&self.aggregate, &right.aggregate);
+            self.reversed = C::combine(&right.reversed, &self.reversed);
+        }
+    }
+
+    fn toggle(&mut self) {
+        core::mem::swap(&mut self.aggregate, &mut self.reversed);
+        self.reverse = !self.reverse;
+    }
+
+    fn push(&mut self) {
+        if self.reverse {
+            core::mem::swap(&mut self.left, &mut self.right);
+            for child in [&mut self.left, &mut self.right].into_iter().flatten() {
+                child.toggle();
+            }
+            self.reverse = false;
+        }
+    }
+}
+
+fn size<C>(link: &Link<C>) -> usize
+where
+    C: SegmentTreeComputation,
+{
+    link.as_ref().map_or(0, |node| node.size)
+}
+
+fn mix(mut value: u64) -> u64 {
+    value = (value ^ (value >> 30)).wrapping_mul(0xbf58_476d_1ce4_e5b9);
+    value = (value ^ (value >> 27)).wrapping_mul(0x94d0_49bb_1331_11eb);
+    value ^ (value >> 31)
+}
+
+/// Splits off the first `count` elements.
+fn split<C>(link: Link<C>, count: usize) -> (Link<C>, Link<C>)
+where
+    C: SegmentTreeComputation,
+    C::Output: Copy,
+{
+    let Some(mut node) = link else {
+        return (None, None);
+    };
+
+    node.push();
+    let left_size = size(&node.left);
+
+    if count <= left_size {
+        let (left, right) = split(node.left.take(), count);
+        node.left = right;
+        node.update();
+        (left, Some(node))
+    } else {
+        let (left, right) = split(node.right.take(), count - left_size - 1);
+        node.right = left;
+        node.update();
+        (Some(node), right)
+    }
+}
+
+fn merge<C>(left: Link<C>, right: Link<C>) -> Link<C>
+where
+    C: SegmentTreeComputation,
+    C::Output: Copy,
+{
+    match (left, right) {
+        (None, link) | (link, None) => link,
+        (Some(mut left), Some(mut right)) => {
+            if left.priority > right.priority {
+                left.push();
+                left.right = merge(left.right.take(), Some(right));
+                left.update();
+                Some(left)
+            } else {
+                right.push();
+                right.left = merge(Some(left), right.left.take());
+                right.update();
+                Some(right)
+            }
+        }
+    }
+}
+
+/// Aggregate of `[left, right]` within the subtree, read backwards when
+/// `flipped` is set. Pending reversals are applied on the fly instead of being
+/// pushed down, so queries don't need `&mut`.
+fn fold<C>(node: &Node<C>, left: usize, right: usize, flipped: bool) -> C::Output
+where
+    C: SegmentTreeComputation,
+    C::Output: Copy,
+{
+    if left == 0 && right + 1 == node.size {
+        return if flipped {
+            node.reversed
+        } else {
+            node.aggregate
+        };
+    }
+
+    let flipped = flipped != node.reverse;
+    let (first, second) = if flipped {
+        (&node.right, &node.left)
+    } else {
+        (&node.left, &node.right)
+    };
+    let first_size = size(first);
+
+    let mut result = None;
+    if let Some(first) = first.as_ref().filter(|_| left < first_size) {
+        result = Some(fold(first, left, right.min(first_size - 1), flipped));
+    }
+    if (left..=right).contains(&first_size) {
+        result = Some(append::<C>(result, node.value));
+    }
+    if let Some(second) = second.as_ref().filter(|_| right > first_size) {
+        let from = left.max(first_size + 1) - first_size - 1;
+        let value = fold(second, from, right - first_size - 1, flipped);
+        result = Some(append::<C>(result, value));
+    }
+
+    result.expect("A non-empty range covers at least one node")
+}
+
+fn modify<C>(node: &mut Node<C>, pos: usize, value: &C::Input)
+where
+    C: SegmentTreeComputation,
+    C::Output: Copy,
+{
+    node.push();
+    let left_size = size(&node.left);
+
+    match (pos.cmp(&left_size), &mut node.left, &mut node.right) {
+        (Ordering::Less, Some(left), _) => modify(left, pos, value),
+        (Ordering::Greater, _, Some(right)) => modify(right, pos - left_size - 1, value),
+        _ => node.value = C::update(&node.value, value),
+    }
+
+    node.update();
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{ImplicitTreap, SumImplicitTreap};
+    use crate::{
+        computation::{
+            MaxSliceSum, MaxSliceSumComputation, RollingHash, RollingHashComputation,
+            SegmentTreeComputation,
+        },
+        testing::{Rng, Source},
+        SegmentTreeError,
+    };
+    use std::fmt::Debug;
+
+    fn verify<C>(treap: &ImplicitTreap<C::Input, C::Output, C>, expected: &[C::Input])
+    where
+        C: SegmentTreeComputation,
+        C::Output: Copy + PartialEq + Debug,
+    {
+        assert_eq!(treap.len(), expected.len());
+
+        for left in 0..expected.len() {
+            let mut result = C::init(&expected[left]);
+            assert_eq!(treap.get(left, left), Ok(result));
+
+            for (right, value) in expected.iter().enumerate().skip(left + 1) {
+                result = C::combine(&result, &C::init(value));
+                assert_eq!(treap.get(left, right), Ok(result));
+            }
+        }
+    }
+
+    fn run_random_operations<C, G>(seed: u64, mut generate: G)
+    where
+        C: SegmentTreeComputation,
+        C::Input: Clone,
+        C::Output: Copy + PartialEq + Debug,
+        G: FnMut(&mut Rng) -> C::Input,
+    {
+        let mut rng = Rng::new(seed);
+        let mut expected: Vec<C::Input> = (0..20).map(|_| generate(&mut rng)).collect();
+        let mut treap = ImplicitTreap::<_, _, C>::build(&expected);
+
+        for _ in 0..200 {
+            match rng.below(5) {
+                0 => {
+                    let pos = rng.below(expected.len() + 1);
+                    let value = generate(&mut rng);
+                    assert_eq!(treap.insert(pos, &value), Ok(()));
+                    expected.insert(pos, value);
+                }
+                1 if !expected.is_empty() => {
+                    let pos = rng.below(expected.len());
+                    let removed = expected.remove(pos);
+                    assert_eq!(treap.remove(pos), Ok(C::init(&removed)));
+                }
+                2 if !expected.is_empty() => {
+                    let left = rng.below(expected.len());
+                    let right = left + rng.below(expected.len() - left);
+                    assert_eq!(treap.reverse(left, right), Ok(()));
+                    expected[left..=right].reverse();
+                }
+                3 if !expected.is_empty() => {
+                    let pos = rng.below(expected.len());
+                    let value = generate(&mut rng);
+                    assert_eq!(treap.modify(pos, &value), Ok(()));
+                    expected[pos] = value;
+                }
+                _ => {
+                    let pos = rng.below(expected.len() + 1);
+                    let tail = treap.split(pos).unwrap();
+                    assert_eq!(tail.len(), expected.len() - pos);
+                    treap.concat(tail);
+                }
+            }
+
+            verify(&treap, &expected);
+        }
+    }
+
+    #[test]
+    fn test_random_operations() {
+        run_random_operations::<RollingHashComputation<u8>, _>(1, |rng| b'a' + rng.below(26) as u8);
+        run_random_operations::<MaxSliceSumComputation<i64>, _>(2, |rng| rng.range_i64(-20, 20));
+    }
+
+    #[test]
+    fn test_split_and_concat() {
+        let text = b"hello, world";
+        let mut treap = ImplicitTreap::<_, _, RollingHashComputation<u8>>::build(text);
+
+        let mut tail = treap.split(5).unwrap();
+        let world = tail.split(2).unwrap();
+        assert_eq!(treap.get(0, 4), Ok(RollingHash::from_slice(b"hello")));
+        assert_eq!(world.get(0, 4), Ok(RollingHash::from_slice(b"world")));
+        assert_eq!(tail.len(), 2);
+
+        let mut reordered = world;
+        reordered.concat(tail);
+        reordered.concat(treap);
+        assert_eq!(
+            reordered.get(0, 11),
+            Ok(RollingHash::from_slice(b"world, hello"))
+        );
+
+        reordered.reverse(0, 11).unwrap();
+        assert_eq!(
+            reordered.get(0, 11),
+            Ok(RollingHash::from_slice(b"olleh ,dlrow"))
+        );
+    }
+
+    #[test]
+    fn test_reverse_max_slice_sum() {
+        let mut treap = ImplicitTreap::<_, _, MaxSliceSumComputation<i32>>::build(&[3, -10, 1, 1]);
+
+        assert_eq!(treap.reverse(1, 3), Ok(()));
+        assert_eq!(
+            treap.get(0, 3),
+            Ok(MaxSliceSum::from_slice(&[3, 1, 1, -10]))
+        );
+        assert_eq!(treap.get(0, 3).unwrap().best_prefix(), &5);
+    }
+
+    #[test]
+    fn test_balanced() {
+        fn depth<C: SegmentTreeComputation>(link: &super::Link<C>) -> usize {
+            link.as_ref()
+                .map_or(0, |node| 1 + depth(&node.left).max(depth(&node.right)))
+        }
+
+        let values: Vec<i64> = (0..2048).collect();
+        let mut treap = SumImplicitTreap::build(&values);
+        treap.concat(SumImplicitTreap::build(&values));
+        for value in 0..1024 {
+            assert_eq!(treap.insert(0, &value), Ok(()));
+        }
+        let rest = treap.split(2560).unwrap();
+
+        // Expected depth is about 2 * log2(n), i.e. 24 for the 5120 elements.
+        assert!(depth(&treap.root) <= 48);
+        assert!(depth(&rest.root) <= 48);
+        assert_eq!(
+            treap.get(0, 2559).unwrap() + rest.get(0, 2559).unwrap(),
+            2 * 2047 * 1024 + 1023 * 512
+        );
+    }
+
+    #[test]
+    fn test_errors() {
+        let mut treap = SumImplicitTreap::build(&[1, 2, 3]);
+        let (left, right) = (2, 1);
+
+        assert_eq!(
+            treap.get(left, right),
+            Err(SegmentTreeError::InvalidRange { left, right })
+        );
+        assert_eq!(
+            treap.reverse(left, right),
+            Err(SegmentTreeError::InvalidRange { left, right })
+        );
+        assert_eq!(
+            treap.get(0, 3),
+            Err(SegmentTreeError::OutOfBounds { index: 3, len: 3 })
+        );
+        assert_eq!(
+            treap.insert(4, &1),
+            Err(SegmentTreeError::OutOfBounds { index: 4, len: 3 })
+        );
+        assert_eq!(
+            treap.remove(3),
+            Err(SegmentTreeError::OutOfBounds { index: 3, len: 3 })
+        );
+        assert_eq!(
+            treap.split(4).err(),
+            Some(SegmentTreeError::OutOfBounds { index: 4, len: 3 })
+        );
+
+        let mut empty = SumImplicitTreap::<i32>::new();
+        assert!(empty.is_empty());
+        assert_eq!(
+            empty.get(0, 0),
+            Err(SegmentTreeError::OutOfBounds { index: 0, len: 0 })
+        );
+        assert_eq!(empty.insert(0, &5), Ok(()));
+        assert_eq!(empty.get(0, 0), Ok(5));
+        assert_eq!(empty.remove(0), Ok(5));
+        assert!(empty.is_empty());
+    }
+}
@@ -1,9 +1,59 @@
+#![cfg_attr(not(any(feature = "std", test)), no_std)]
+
+extern crate alloc;
+
 pub use crate::{
-    computation::SegmentTreeComputation,
+    array_segment_tree::{ArraySegmentTree, MaxArraySegmentTree, SumArraySegmentTree},
+    atomic_segment_tree::{AtomicInteger, AtomicMaxSegmentTree, AtomicSumSegmentTree},
+    bracket_sequence::BracketSequence,
+    computation::{
+        BitAndComputation, BitOrComputation, BracketBalance, BracketComputation, GcdComputation,
+        GcdInteger, IdempotentComputation, InvertibleComputation, MaxComputation, MaxSliceSum,
+        MaxSliceSumComputation, MinComputation, ModularProductComputation, RollingHash,
+        RollingHashComputation, SegmentTreeComputation, SumComputation, XorComputation,
+    },
     errors::{SegmentTreeError, SegmentTreeResult},
-    segment_tree::{MaxSegmentTree, MaxSliceSumSegmentTree, SegmentTree, SumSegmentTree},
+    fenwick_tree::{
+        FenwickTree, ModularProductFenwickTree, ModularProductRangeFenwickTree, RangeFenwickTree,
+        SumFenwickTree, SumRangeFenwickTree, XorFenwickTree, XorRangeFenwickTree,
+    },
+    fixed_segment_tree::{FixedSegmentTree, MaxFixedSegmentTree, SumFixedSegmentTree},
+    implicit_treap::{ImplicitTreap, MaxImplicitTreap, SumImplicitTreap},
+    range_query::{NaiveRangeQuery, PointUpdate, RangeQuery},
+    segment_tree::{
+        BracketSegmentTree, Checkpoint, CompactLayout, HeapLayout, Layout, MaxSegmentTree,
+        MaxSliceSumSegmentTree, RollingHashSegmentTree, SegmentTree, SumSegmentTree, VebLayout,
+    },
+    sparse_segment_tree::{MaxSparseSegmentTree, SparseSegmentTree, SumSparseSegmentTree},
+    sparse_table::{
+        BitAndSparseTable, BitOrSparseTable, GcdSparseTable, MaxSparseTable, MinSparseTable,
+        SparseTable,
+    },
+    substring_hasher::SubstringHasher,
+    wide_segment_tree::{MaxWideSegmentTree, SumWideSegmentTree, WideSegmentTree},
+};
+
+#[cfg(feature = "std")]
+pub use crate::segment_tree::{
+    ConcurrentMaxSegmentTree, ConcurrentMaxSliceSumSegmentTree, ConcurrentSegmentTree,
+    ConcurrentSumSegmentTree,
 };
+#[cfg(feature = "trace")]
+pub use crate::segment_tree::{OperationStatistics, Statistics, Trace};
 
+mod array_segment_tree;
+mod atomic_segment_tree;
+mod bracket_sequence;
 mod computation;
 mod errors;
+mod fenwick_tree;
+mod fixed_segment_tree;
+mod implicit_treap;
+mod range_query;
 mod segment_tree;
+mod sparse_segment_tree;
+mod sparse_table;
+mod substring_hasher;
+#[cfg(any(test, feature = "testing"))]
+pub mod testing;
+mod wide_segment_tree;
@@ -0,0 +1,540 @@
+use crate::{
+    array_segment_tree::ArraySegmentTree,
+    computation::{IdempotentComputation, InvertibleComputation, SegmentTreeComputation},
+    errors::{SegmentTreeError, SegmentTreeResult},
+    fenwick_tree::{FenwickTree, RangeFenwickTree},
+    fixed_segment_tree::FixedSegmentTree,
+    implicit_treap::ImplicitTreap,
+    segment_tree::{Layout, SegmentTree},
+    sparse_segment_tree::SparseSegmentTree,
+    sparse_table::SparseTable,
+    wide_segment_tree::WideSegmentTree,
+};
+use alloc::vec::Vec;
+use core::marker::PhantomData;
+
+#[cfg(feature = "std")]
+use crate::segment_tree::ConcurrentSegmentTree;
+
+pub trait RangeQuery {
+    type Output;
+
+    fn get(&self, left: usize, right: usize) -> SegmentTreeResult<Self::Output>;
+
+    fn len(&self) -> usize;
+
+    fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+}
+
+pub trait PointUpdate: RangeQuery {
+    type Input;
+
+    fn modify(&mut self, pos: usize, value: &Self::Input) -> SegmentTreeResult<()>;
+}
+
+/// Reference implementation that combines every element of the range on each query.
+pub struct NaiveRangeQuery<I, O, C>
+where
+    C: SegmentTreeComputation,
+{
+    data: Vec<O>,
+    phantom: PhantomData<(C, I)>,
+}
+
+impl<I, O, C> NaiveRangeQuery<I, O, C>
+where
+    O: Clone,
+    C: SegmentTreeComputation<Input = I, Output = O>,
+{
+    pub fn build(arr: &[I]) -> Self {
+        Self {
+            data: arr.iter().map(C::init).collect(),
+            phantom: PhantomData,
+        }
+    }
+}
+
+impl<I, O, C> RangeQuery for NaiveRangeQuery<I, O, C>
+where
+    O: Clone,
+    C: SegmentTreeComputation<Input = I, Output = O>,
+{
+    type Output = O;
+
+    fn get(&self, left: usize, right: usize) -> SegmentTreeResult<O> {
+        if left > right {
+            Err(SegmentTreeError::InvalidRange { left, right })
+        } else if right >= self.data.len() {
+            Err(SegmentTreeError::OutOfBounds {
+                index: right,
+                len: self.data.len(),
+            })
+        } else {
+            let result = self.data[left + 1..=right]
+                .iter()
+                .fold(self.data[left].clone(), |acc, cur| C::combine(&acc, cur));
+
+            Ok(result)
+        }
+    }
+
+    fn len(&self) -> usize {
+        self.data.len()
+    }
+}
+
+impl<I, O, C> PointUpdate for NaiveRangeQuery<I, O, C>
+where
+    O: Clone,
+    C: SegmentTreeComputation<Input = I, Output = O>,
+{
+    type Input = I;
+
+    fn modify(&mut self, pos: usize, value: &I) -> SegmentTreeResult<()> {
+        let len = self.data.len();
+        let prev_value = self
+            .data
+            .get_mut(pos)
+            .ok_or(SegmentTreeError::OutOfBounds { index: pos, len })?;
+
+        *prev_value = C::update(prev_value, value);
+        Ok(())
+    }
+}
+
+impl<I, O, C, L> RangeQuery for SegmentTree<I, O, C, L>
+where
+    O: Default + Clone + Copy,
+    C: SegmentTreeComputation<Input = I, Output = O>,
+    L: Layout,
+{
+    type Output = O;
+
+    fn get(&self, left: usize, right: usize) -> SegmentTreeResult<O> {
+        SegmentTree::get(self, left, right)
+    }
+
+    fn len(&self) -> usize {
+        SegmentTree::len(self)
+    }
+}
+
+impl<I, O, C, L> PointUpdate for SegmentTree<I, O, C, L>
+where
+    O: Default + Clone + Copy,
+    C: SegmentTreeComputation<Input = I, Output = O>,
+    L: Layout,
+{
+    type Input = I;
+
+    fn modify(&mut self, pos: usize, value: &I) -> SegmentTreeResult<()> {
+        SegmentTree::modify(self, pos, value)
+    }
+}
+
+#[cfg(feature = "std")]
+impl<I, O, C> RangeQuery for ConcurrentSegmentTree<I, O, C>
+where
+    O: Default + Clone + Copy,
+    C: SegmentTreeComputation<Input = I, Output = O>,
+{
+    type Output = O;
+
+    fn get(&self, left: usize, right: usize) -> SegmentTreeResult<O> {
+        ConcurrentSegmentTree::get(self, left, right)
+    }
+
+    fn len(&self) -> usize {
+        ConcurrentSegmentTree::len(self)
+    }
+}
+
+#[cfg(feature = "std")]
+impl<I, O, C> PointUpdate for ConcurrentSegmentTree<I, O, C>
+where
+    I: Clone,
+    O: Default + Clone + Copy,
+    C: SegmentTreeComputation<Input = I, Output = O>,
+{
+    type Input = I;
+
+    fn modify(&mut self, pos: usize, value: &I) -> SegmentTreeResult<()> {
+        ConcurrentSegmentTree::modify(self, pos, value)
+    }
+}
+
+impl<I, O, C> RangeQuery for FenwickTree<I, O, C>
+where
+    O: Clone,
+    C: InvertibleComputation<Input = I, Output = O>,
+{
+    type Output = O;
+
+    fn get(&self, left: usize, right: usize) -> SegmentTreeResult<O> {
+        FenwickTree::get(self, left, right)
+    }
+
+    fn len(&self) -> usize {
+        FenwickTree::len(self)
+    }
+}
+
+impl<I, O, C> PointUpdate for FenwickTree<I, O, C>
+where
+    O: Clone,
+    C: InvertibleComputation<Input = I, Output = O>,
+{
+    type Input = I;
+
+    fn modify(&mut self, pos: usize, value: &I) -> SegmentTreeResult<()> {
+        FenwickTree::modify(self, pos, value)
+    }
+}
+
+impl<I, O, C> RangeQuery for RangeFenwickTree<I, O, C>
+where
+    O: Clone,
+    C: InvertibleComputation<Input = I, Output = O>,
+{
+    type Output = O;
+
+    fn get(&self, left: usize, right: usize) -> SegmentTreeResult<O> {
+        RangeFenwickTree::get(self, left, right)
+    }
+
+    fn len(&self) -> usize {
+        RangeFenwickTree::len(self)
+    }
+}
+
+impl<I, O, C> PointUpdate for RangeFenwickTree<I, O, C>
+where
+    O: Clone,
+    C: InvertibleComputation<Input = I, Output = O>,
+{
+    type Input = I;
+
+    fn modify(&mut self, pos: usize, value: &I) -> SegmentTreeResult<()> {
+        RangeFenwickTree::modify(self, pos, value)
+    }
+}
+
+impl<I, O, C> RangeQuery for SparseTable<I, O, C>
+where
+    O: Clone,
+    C: IdempotentComputation<Input = I, Output = O>,
+{
+    type Output = O;
+
+    fn get(&self, left: usize, right: usize) -> SegmentTreeResult<O> {
+        SparseTable::get(self, left, right)
+    }
+
+    fn len(&self) -> usize {
+        SparseTable::len(self)
+    }
+}
+
+impl<I, O, C, const B: usize> RangeQuery for WideSegmentTree<I, O, C, B>
+where
+    O: Copy,
+    C: SegmentTreeComputation<Input = I, Output = O>,
+{
+    type Output = O;
+
+    fn get(&self, left: usize, right: usize) -> SegmentTreeResult<O> {
+        WideSegmentTree::get(self, left, right)
+    }
+
+    fn len(&self) -> usize {
+        WideSegmentTree::len(self)
+    }
+}
+
+impl<I, O, C, const B: usize> PointUpdate for WideSegmentTree<I, O, C, B>
+where
+    O: Copy,
+    C: SegmentTreeComputation<Input = I, Output = O>,
+{
+    type Input = I;
+
+    fn modify(&mut self, pos: usize, value: &I) -> SegmentTreeResult<()> {
+        WideSegmentTree::modify(self, pos, value)
+    }
+}
+
+impl<C, const N: usize> RangeQuery for ArraySegmentTree<C, N>
+where
+    C: SegmentTreeComputation,
+    C::Output: Copy,
+{
+    type Output = C::Output;
+
+    fn get(&self, left: usize, right: usize) -> SegmentTreeResult<C::Output> {
+        ArraySegmentTree::get(self, left, right)
+    }
+
+    fn len(&self) -> usize {
+        ArraySegmentTree::len(self)
+    }
+}
+
+impl<C, const N: usize> PointUpdate for ArraySegmentTree<C, N>
+where
+    C: SegmentTreeComputation,
+    C::Output: Copy,
+{
+    type Input = C::Input;
+
+    fn modify(&mut self, pos: usize, value: &C::Input) -> SegmentTreeResult<()> {
+        ArraySegmentTree::modify(self, pos, value)
+    }
+}
+
+impl<I, O, C, const N: usize> RangeQuery for FixedSegmentTree<I, O, C, N>
+where
+    O: Default + Copy,
+    C: SegmentTreeComputation<Input = I, Output = O>,
+{
+    type Output = O;
+
+    fn get(&self, left: usize, right: usize) -> SegmentTreeResult<O> {
+        FixedSegmentTree::get(self, left, right)
+    }
+
+    fn len(&self) -> usize {
+        FixedSegmentTree::len(self)
+    }
+}
+
+impl<I, O, C, const N: usize> PointUpdate for FixedSegmentTree<I, O, C, N>
+where
+    O: Default + Copy,
+    C: SegmentTreeComputation<Input = I, Output = O>,
+{
+    type Input = I;
+
+    fn modify(&mut self, pos: usize, value: &I) -> SegmentTreeResult<()> {
+        FixedSegmentTree::modify(self, pos, value)
+    }
+}
+
+impl<I, O, C> RangeQuery for ImplicitTreap<I, O, C>
+where
+    O: Copy,
+    C: SegmentTreeComputation<Input = I, Output = O>,
+{
+    type Output = O;
+
+    fn get(&self, left: usize, right: usize) -> SegmentTreeResult<O> {
+        ImplicitTreap::get(self, left, right)
+    }
+
+    fn len(&self) -> usize {
+        ImplicitTreap::len(self)
+    }
+}
+
+impl<I, O, C> PointUpdate for ImplicitTreap<I, O, C>
+where
+    O: Copy,
+    C: SegmentTreeComputation<Input = I, Output = O>,
+{
+    type Input = I;
+
+    fn modify(&mut self, pos: usize, value: &I) -> SegmentTreeResult<()> {
+        ImplicitTreap::modify(self, pos, value)
+    }
+}
+
+impl<I, O, C> RangeQuery for SparseSegmentTree<I, O, C>
+where
+    O: Copy,
+    C: SegmentTreeComputation<Input = I, Output = O>,
+{
+    type Output = Option<O>;
+
+    fn get(&self, left: usize, right: usize) -> SegmentTreeResult<Option<O>> {
+        SparseSegmentTree::get(self, left, right)
+    }
+
+    fn len(&self) -> usize {
+        SparseSegmentTree::len(self)
+    }
+}
+
+impl<I, O, C> PointUpdate for SparseSegmentTree<I, O, C>
+where
+    O: Copy,
+    C: SegmentTreeComputation<Input = I, Output = O>,
+{
+    type Input = I;
+
+    fn modify(&mut self, pos: usize, value: &I) -> SegmentTreeResult<()> {
+        SparseSegmentTree::modify(self, pos, value)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{NaiveRangeQuery, PointUpdate, RangeQuery};
+    use crate::{
+        computation::{MaxComputation, MaxSliceSumComputation, SumComputation},
+        ArraySegmentTree, FixedSegmentTree, ImplicitTreap, MaxArraySegmentTree,
+        MaxFixedSegmentTree, MaxSegmentTree, MaxSliceSumSegmentTree, MaxSparseTable,
+        MaxWideSegmentTree, SegmentTreeError, SumArraySegmentTree, SumFenwickTree,
+        SumFixedSegmentTree, SumImplicitTreap, SumRangeFenwickTree, SumSegmentTree,
+        SumWideSegmentTree, WideSegmentTree,
+    };
+    use std::fmt::Debug;
+
+    #[cfg(feature = "std")]
+    use crate::ConcurrentSumSegmentTree;
+
+    fn verify_queries<T, R>(actual: &T, expected: &R)
+    where
+        T: RangeQuery,
+        R: RangeQuery<Output = T::Output>,
+        T::Output: PartialEq + Debug,
+    {
+        assert_eq!(actual.len(), expected.len());
+        assert_eq!(actual.is_empty(), expected.is_empty());
+
+        for left in 0..=actual.len() {
+            for right in 0..=actual.len() {
+                assert_eq!(actual.get(left, right), expected.get(left, right));
+            }
+        }
+    }
+
+    fn verify_updates<T, R>(actual: &mut T, expected: &mut R, updates: &[(usize, T::Input)])
+    where
+        T: PointUpdate,
+        R: PointUpdate<Input = T::Input, Output = T::Output>,
+        T::Output: PartialEq + Debug,
+    {
+        for (pos, value) in updates {
+            assert_eq!(actual.modify(*pos, value), expected.modify(*pos, value));
+
+            verify_queries(actual, expected);
+        }
+    }
+
+    #[test]
+    fn test_naive_range_query() {
+        let arr = [1, 3, 7, 27];
+        let mut naive = NaiveRangeQuery::<_, _, SumComputation<i32>>::build(&arr);
+
+        assert_eq!(RangeQuery::get(&naive, 1, 3), Ok(37));
+        assert_eq!(
+            RangeQuery::get(&naive, 2, 1),
+            Err(SegmentTreeError::InvalidRange { left: 2, right: 1 })
+        );
+        assert_eq!(
+            RangeQuery::get(&naive, 0, 4),
+            Err(SegmentTreeError::OutOfBounds { index: 4, len: 4 })
+        );
+        assert_eq!(naive.modify(0, &10), Ok(()));
+        assert_eq!(RangeQuery::get(&naive, 0, 1), Ok(13));
+        assert_eq!(
+            naive.modify(4, &10),
+            Err(SegmentTreeError::OutOfBounds { index: 4, len: 4 })
+        );
+    }
+
+    #[test]
+    fn test_sum_structures() {
+        let arr = [1, -3, 7, 27, 73, 0, -12];
+        let updates = [(3, 10), (0, -5), (6, 6), (7, 1)];
+        let mut naive = NaiveRangeQuery::<_, _, SumComputation<i64>>::build(&arr);
+
+        verify_updates(&mut SumSegmentTree::build(&arr), &mut naive, &updates);
+
+        let mut naive = NaiveRangeQuery::<_, _, SumComputation<i64>>::build(&arr);
+        verify_updates(&mut SumFenwickTree::build(&arr), &mut naive, &updates);
+
+        let mut naive = NaiveRangeQuery::<_, _, SumComputation<i64>>::build(&arr);
+        verify_updates(&mut SumRangeFenwickTree::build(&arr), &mut naive, &updates);
+
+        #[cfg(feature = "std")]
+        {
+            let mut naive = NaiveRangeQuery::<_, _, SumComputation<i64>>::build(&arr);
+            verify_updates(
+                &mut ConcurrentSumSegmentTree::build(&arr),
+                &mut naive,
+                &updates,
+            );
+        }
+
+        let mut naive = NaiveRangeQuery::<_, _, SumComputation<i64>>::build(&arr);
+        verify_updates(&mut SumWideSegmentTree::build(&arr), &mut naive, &updates);
+
+        let mut naive = NaiveRangeQuery::<_, _, SumComputation<i64>>::build(&arr);
+        verify_updates(
+            &mut SumFixedSegmentTree::<_, 8>::build(&arr).unwrap(),
+            &mut naive,
+            &updates,
+        );
+
+        let mut naive = NaiveRangeQuery::<_, _, SumComputation<i64>>::build(&arr);
+        verify_updates(&mut SumArraySegmentTree::build(&arr), &mut naive, &updates);
+
+        let mut naive = NaiveRangeQuery::<_, _, SumComputation<i64>>::build(&arr);
+        verify_updates(&mut SumImplicitTreap::build(&arr), &mut naive, &updates);
+    }
+
+    #[test]
+    fn test_max_structures() {
+        let arr = [1, 3, 7, 27, 73, 7542, 1, -5, -543, 9];
+        let naive = NaiveRangeQuery::<_, _, MaxComputation<i32>>::build(&arr);
+
+        verify_queries(&MaxSegmentTree::build(&arr), &naive);
+        verify_queries(&MaxSparseTable::build(&arr), &naive);
+        verify_queries(&MaxWideSegmentTree::build(&arr), &naive);
+        verify_queries(&MaxFixedSegmentTree::<_, 16>::build(&arr).unwrap(), &naive);
+        verify_queries(&MaxArraySegmentTree::build(&arr), &naive);
+    }
+
+    #[test]
+    fn test_max_slice_sum_structures() {
+        let arr = [-2, 1, 2, 1, -10, 7, 2, -11, 4];
+        let updates = [(4, 10), (7, 0), (0, 5)];
+        let mut naive = NaiveRangeQuery::<_, _, MaxSliceSumComputation<i32>>::build(&arr);
+
+        verify_updates(
+            &mut MaxSliceSumSegmentTree::build(&arr),
+            &mut naive,
+            &updates,
+        );
+
+        let mut naive = NaiveRangeQuery::<_, _, MaxSliceSumComputation<i32>>::build(&arr);
+        verify_updates(
+            &mut WideSegmentTree::<_, _, MaxSliceSumComputation<i32>, 3>::build(&arr),
+            &mut naive,
+            &updates,
+        );
+
+        let mut naive = NaiveRangeQuery::<_, _, MaxSliceSumComputation<i32>>::build(&arr);
+        verify_updates(
+            &mut FixedSegmentTree::<_, _, MaxSliceSumComputation<i32>, 9>::build(&arr).unwrap(),
+            &mut naive,
+            &updates,
+        );
+
+        let mut naive = NaiveRangeQuery::<_, _, MaxSliceSumComputation<i32>>::build(&arr);
+        verify_updates(
+            &mut ArraySegmentTree::<MaxSliceSumComputation<i32>, 9>::build(&arr),
+            &mut naive,
+            &updates,
+        );
+
+        let mut naive = NaiveRangeQuery::<_, _, MaxSliceSumComputation<i32>>::build(&arr);
+        verify_updates(
+            &mut ImplicitTreap::<_, _, MaxSliceSumComputation<i32>>::build(&arr),
+            &mut naive,
+            &updates,
+        );
+    }
+}
@@ -1,51 +1,145 @@
 use crate::{
     computation::{
-        MaxComputation, MaxSliceSum, MaxSliceSumComputation, SegmentTreeComputation, SumComputation,
+        BracketBalance, BracketComputation, MaxComputation, MaxSliceSum, MaxSliceSumComputation,
+        RollingHash, RollingHashComputation, SegmentTreeComputation, SumComputation,
     },
     errors::{SegmentTreeError, SegmentTreeResult},
 };
-use std::marker::PhantomData;
-
-pub struct SegmentTree<I, O, C>
+use alloc::{vec, vec::Vec};
+use core::marker::PhantomData;
+use invariants::InvariantCheck;
+use journal::Journal;
+use trace::{Counters, DefaultTracer, Tracer};
+use traversal::{Builder, Nodes};
+
+#[cfg(feature = "std")]
+pub use concurrent::{
+    ConcurrentMaxSegmentTree, ConcurrentMaxSliceSumSegmentTree, ConcurrentSegmentTree,
+    ConcurrentSumSegmentTree,
+};
+pub use journal::Checkpoint;
+pub use layout::{CompactLayout, HeapLayout, Layout, VebLayout};
+pub(crate) use trace::NoopTracer;
+#[cfg(feature = "trace")]
+pub use trace::{OperationStatistics, Statistics, Trace};
+
+#[cfg(feature = "std")]
+mod concurrent;
+mod invariants;
+mod journal;
+mod layout;
+mod trace;
+pub(crate) mod traversal;
+mod visualize;
+
+pub struct SegmentTree<I, O, C, L = HeapLayout>
 where
     C: SegmentTreeComputation,
 {
     data: Vec<O>,
     len: usize,
-    phantom: PhantomData<(C, I)>,
+    journal: Journal<O>,
+    counters: Counters,
+    invariants: InvariantCheck<SegmentTree<I, O, C, L>>,
+    phantom: PhantomData<(C, I, L)>,
 }
 
-pub type SumSegmentTree<T> = SegmentTree<T, T, SumComputation<T>>;
-pub type MaxSegmentTree<T> = SegmentTree<T, T, MaxComputation<T>>;
-pub type MaxSliceSumSegmentTree<T> = SegmentTree<T, MaxSliceSum<T>, MaxSliceSumComputation<T>>;
+pub type SumSegmentTree<T, L = HeapLayout> = SegmentTree<T, T, SumComputation<T>, L>;
+pub type MaxSegmentTree<T, L = HeapLayout> = SegmentTree<T, T, MaxComputation<T>, L>;
+pub type MaxSliceSumSegmentTree<T, L = HeapLayout> =
+    SegmentTree<T, MaxSliceSum<T>, MaxSliceSumComputation<T>, L>;
+pub type BracketSegmentTree<L = HeapLayout> =
+    SegmentTree<char, BracketBalance, BracketComputation, L>;
+pub type RollingHashSegmentTree<T, L = HeapLayout> =
+    SegmentTree<T, RollingHash, RollingHashComputation<T>, L>;
 
 impl<I, O, C> SegmentTree<I, O, C>
 where
-    O: Default + Clone + Copy,
+    O: Default + Clone + Copy + PartialEq,
     C: SegmentTreeComputation<Input = I, Output = O>,
 {
     pub fn build(arr: &[I]) -> Self {
-        if arr.is_empty() {
-            Self {
-                data: vec![],
-                len: 0,
-                phantom: PhantomData,
-            }
-        } else {
-            let len = arr.len();
-            let mut data = vec![O::default(); len * 4];
+        Self::from_input(arr, &mut DefaultTracer::default())
+    }
+}
+
+// Constructors need `PartialEq` to install the invariant check, which keeps the
+// bound off the mutating methods.
+impl<I, O, C, L> SegmentTree<I, O, C, L>
+where
+    O: Default + Clone + Copy + PartialEq,
+    C: SegmentTreeComputation<Input = I, Output = O>,
+    L: Layout,
+{
+    /// Builds the tree with nodes stored in `layout` order.
+    pub fn build_in(arr: &[I], _layout: L) -> Self {
+        Self::from_input(arr, &mut DefaultTracer::default())
+    }
 
-            Self::internal_build(arr, &mut data, 1, 0, len - 1);
+    fn from_input(arr: &[I], tracer: &mut DefaultTracer) -> Self {
+        let len = arr.len();
+        let mut builder = InputBuilder::<I, O, C, L> {
+            input: arr,
+            data: if len == 0 {
+                vec![]
+            } else {
+                vec![O::default(); L::capacity(len)]
+            },
+            phantom: PhantomData,
+        };
 
-            Self {
-                data,
-                len,
-                phantom: PhantomData,
-            }
+        if len > 0 {
+            traversal::build(&mut builder, 1, 0, len - 1, tracer);
+        }
+
+        Self {
+            data: builder.data,
+            len,
+            journal: Journal::default(),
+            counters: Counters::from_build(tracer),
+            invariants: InvariantCheck::new(Self::validate),
+            phantom: PhantomData,
         }
     }
+}
 
+impl<I, O, C, L> SegmentTree<I, O, C, L>
+where
+    O: Default + Clone + Copy,
+    C: SegmentTreeComputation<Input = I, Output = O>,
+    L: Layout,
+{
     pub fn get(&self, left: usize, right: usize) -> SegmentTreeResult<O> {
+        self.check_range(left, right)?;
+
+        let mut tracer = DefaultTracer::default();
+        let result = traversal::fold(self, 1, 0, self.len - 1, left, right, &mut tracer);
+        self.counters.record_get(&tracer);
+
+        Ok(result)
+    }
+
+    pub fn modify(&mut self, pos: usize, value: &I) -> SegmentTreeResult<()> {
+        self.check_pos(pos)?;
+
+        let mut tracer = DefaultTracer::default();
+        self.internal_modify(1, 0, self.len - 1, pos, value, &mut tracer);
+        self.counters.record_modify(&tracer);
+
+        self.invariants.run(self, format_args!("modifying {pos}"));
+
+        Ok(())
+    }
+
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    fn check_range(&self, left: usize, right: usize) -> SegmentTreeResult<()> {
         if left > right {
             Err(SegmentTreeError::InvalidRange { left, right })
         } else if right >= self.len {
@@ -54,96 +148,108 @@ where
                 len: self.len,
             })
         } else {
-            Ok(self.internal_get(1, 0, self.len - 1, left, right))
+            Ok(())
         }
     }
 
-    pub fn modify(&mut self, pos: usize, value: &I) -> SegmentTreeResult<()> {
+    fn check_pos(&self, pos: usize) -> SegmentTreeResult<()> {
         if pos >= self.len {
             Err(SegmentTreeError::OutOfBounds {
                 index: pos,
                 len: self.len,
             })
         } else {
-            self.internal_modify(1, 0, self.len - 1, pos, value);
             Ok(())
         }
     }
 
-    pub fn len(&self) -> usize {
-        self.len
+    fn slot(&self, index: usize, cur_left: usize, cur_right: usize) -> usize {
+        L::position(index, cur_left, cur_right, self.len)
     }
 
-    pub fn is_empty(&self) -> bool {
-        self.len == 0
+    fn node(&self, index: usize, cur_left: usize, cur_right: usize) -> &O {
+        &self.data[self.slot(index, cur_left, cur_right)]
     }
 
-    fn internal_build(
-        input: &[I],
-        data: &mut Vec<O>,
+    fn internal_modify<T: Tracer>(
+        &mut self,
         index: usize,
         cur_left: usize,
         cur_right: usize,
+        pos: usize,
+        value: &I,
+        tracer: &mut T,
     ) {
-        data[index] = if cur_left == cur_right {
-            C::init(&input[cur_left])
+        tracer.enter(index);
+
+        let slot = self.slot(index, cur_left, cur_right);
+        let new_value = if cur_left == cur_right {
+            C::update(&self.data[slot], value)
         } else {
             let mid = (cur_left + cur_right) / 2;
-            Self::internal_build(input, data, index * 2, cur_left, mid);
-            Self::internal_build(input, data, index * 2 + 1, mid + 1, cur_right);
 
-            C::combine(&data[index * 2], &data[index * 2 + 1])
-        }
+            if pos <= mid {
+                self.internal_modify(index * 2, cur_left, mid, pos, value, tracer);
+            } else {
+                self.internal_modify(index * 2 + 1, mid + 1, cur_right, pos, value, tracer);
+            }
+
+            tracer.combine();
+            C::combine(
+                self.node(index * 2, cur_left, mid),
+                self.node(index * 2 + 1, mid + 1, cur_right),
+            )
+        };
+
+        self.journal.record(slot, &self.data[slot]);
+        self.data[slot] = new_value;
+
+        tracer.exit();
     }
+}
 
-    fn internal_get(
-        &self,
-        index: usize,
-        cur_left: usize,
-        cur_right: usize,
-        left: usize,
-        right: usize,
-    ) -> O {
-        if left == cur_left && right == cur_right {
-            self.data[index]
-        } else {
-            let mid = (cur_left + cur_right) / 2;
+impl<I, O, C, L> Nodes for SegmentTree<I, O, C, L>
+where
+    O: Default + Clone + Copy,
+    C: SegmentTreeComputation<Input = I, Output = O>,
+    L: Layout,
+{
+    type Output = O;
 
-            if mid < left {
-                self.internal_get(index * 2 + 1, mid + 1, cur_right, left, right)
-            } else if mid + 1 > right {
-                self.internal_get(index * 2, cur_left, mid, left, right)
-            } else {
-                let left_result = self.internal_get(index * 2, cur_left, mid, left, right.min(mid));
-                let right_result =
-                    self.internal_get(index * 2 + 1, mid + 1, cur_right, left.max(mid + 1), right);
+    fn node(&self, index: usize, cur_left: usize, cur_right: usize) -> O {
+        *SegmentTree::node(self, index, cur_left, cur_right)
+    }
 
-                C::combine(&left_result, &right_result)
-            }
-        }
+    fn combine(&self, left: &O, right: &O) -> O {
+        C::combine(left, right)
     }
+}
 
-    fn internal_modify(
-        &mut self,
-        index: usize,
-        cur_left: usize,
-        cur_right: usize,
-        pos: usize,
-        value: &I,
-    ) {
-        self.data[index] = if cur_left == cur_right {
-            C::update(&self.data[index], value)
-        } else {
-            let mid = (cur_left + cur_right) / 2;
+/// Builds the nodes of a tree from its input, storing them in `L` order.
+struct InputBuilder<'a, I, O, C, L> {
+    input: &'a [I],
+    data: Vec<O>,
+    phantom: PhantomData<(C, L)>,
+}
 
-            if pos <= mid {
-                self.internal_modify(index * 2, cur_left, mid, pos, value);
-            } else {
-                self.internal_modify(index * 2 + 1, mid + 1, cur_right, pos, value);
-            }
+impl<I, O, C, L> Builder for InputBuilder<'_, I, O, C, L>
+where
+    O: Copy,
+    C: SegmentTreeComputation<Input = I, Output = O>,
+    L: Layout,
+{
+    type Output = O;
 
-            C::combine(&self.data[index * 2], &self.data[index * 2 + 1])
-        }
+    fn leaf(&mut self, pos: usize) -> O {
+        C::init(&self.input[pos])
+    }
+
+    fn combine(&mut self, left: &O, right: &O) -> O {
+        C::combine(left, right)
+    }
+
+    fn store(&mut self, index: usize, cur_left: usize, cur_right: usize, value: &O) {
+        self.data[L::position(index, cur_left, cur_right, self.input.len())] = *value;
     }
 }
 
@@ -160,13 +266,16 @@ mod tests {
         let arr = [1, 3, 7, 27];
         let tree = SumSegmentTree::build(&arr);
 
-        /*
-                  38
-                 /  \
-                4   34
-               / \  / \
-               1 3  7 27
-        */
+        let expected = "\
+#1 [0, 3] = 38
+├── #2 [0, 1] = 4
+│   ├── #4 [0, 0] = 1
+│   └── #5 [1, 1] = 3
+└── #3 [2, 3] = 34
+    ├── #6 [2, 2] = 7
+    └── #7 [3, 3] = 27
+";
+        assert_eq!(tree.to_ascii(), expected);
         assert!(!tree.is_empty());
         assert_eq!(tree.len(), arr.len());
         assert_eq!(tree.data.len(), 16);
@@ -273,13 +382,16 @@ mod tests {
         assert_eq!(tree.modify(3, &10), Ok(()));
         assert_eq!(tree.modify(1, &73), Ok(()));
 
-        /*
-                  91
-                 /  \
-                74   17
-               / \  / \
-               1 73 7 10
-        */
+        let expected = "\
+#1 [0, 3] = 91
+├── #2 [0, 1] = 74
+│   ├── #4 [0, 0] = 1
+│   └── #5 [1, 1] = 73
+└── #3 [2, 3] = 17
+    ├── #6 [2, 2] = 7
+    └── #7 [3, 3] = 10
+";
+        assert_eq!(tree.to_ascii(), expected);
         assert_eq!(tree.data.len(), 16);
         assert_eq!(tree.len(), arr.len());
         assert_eq!(tree.data[0], 0);
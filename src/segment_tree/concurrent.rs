@@ -0,0 +1,419 @@
+use super::{
+    journal::Journal,
+    trace::{Counters, DefaultTracer},
+    SegmentTree,
+};
+use crate::{
+    computation::{
+        MaxComputation, MaxSliceSum, MaxSliceSumComputation, SegmentTreeComputation, SumComputation,
+    },
+    errors::SegmentTreeResult,
+};
+use std::{
+    marker::PhantomData,
+    mem,
+    sync::{Arc, Mutex, PoisonError, RwLock},
+};
+
+/// Segment tree that can be shared between threads without an outer lock.
+///
+/// Queries run on a published snapshot of the tree, and `modify` updates a
+/// second copy and then publishes it. A query only waits for the pointer swap,
+/// never for a running `modify`, and sees the tree between two complete
+/// `modify` calls, i.e. queries and modifications are linearizable.
+///
+/// The unpublished copy lags one `modify` behind and catches up at the start
+/// of the next one, by which time readers have usually left it. If a reader
+/// still holds it, `modify` copies the tree instead of waiting. A panicking
+/// `combine` leaves the published tree unchanged.
+pub struct ConcurrentSegmentTree<I, O, C>
+where
+    C: SegmentTreeComputation,
+{
+    published: RwLock<Arc<SegmentTree<I, O, C>>>,
+    standby: Mutex<Standby<I, O, C>>,
+    len: usize,
+}
+
+/// The writer's copy of the tree and the update it hasn't applied yet.
+struct Standby<I, O, C>
+where
+    C: SegmentTreeComputation,
+{
+    tree: Arc<SegmentTree<I, O, C>>,
+    pending: Option<(usize, I)>,
+    /// Set while the copy is modified, so a panic leaves it marked as stale.
+    stale: bool,
+}
+
+pub type ConcurrentSumSegmentTree<T> = ConcurrentSegmentTree<T, T, SumComputation<T>>;
+pub type ConcurrentMaxSegmentTree<T> = ConcurrentSegmentTree<T, T, MaxComputation<T>>;
+pub type ConcurrentMaxSliceSumSegmentTree<T> =
+    ConcurrentSegmentTree<T, MaxSliceSum<T>, MaxSliceSumComputation<T>>;
+
+impl<I, O, C> ConcurrentSegmentTree<I, O, C>
+where
+    O: Default + Clone + Copy,
+    C: SegmentTreeComputation<Input = I, Output = O>,
+{
+    pub fn build(arr: &[I]) -> Self
+    where
+        O: PartialEq,
+    {
+        SegmentTree::build(arr).into()
+    }
+
+    pub fn get(&self, left: usize, right: usize) -> SegmentTreeResult<O> {
+        self.snapshot().get(left, right)
+    }
+
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    fn snapshot(&self) -> Arc<SegmentTree<I, O, C>> {
+        let published = self
+            .published
+            .read()
+            .unwrap_or_else(PoisonError::into_inner);
+
+        Arc::clone(&published)
+    }
+}
+
+impl<I, O, C> ConcurrentSegmentTree<I, O, C>
+where
+    I: Clone,
+    O: Default + Clone + Copy,
+    C: SegmentTreeComputation<Input = I, Output = O>,
+{
+    pub fn modify(&self, pos: usize, value: &I) -> SegmentTreeResult<()> {
+        let mut standby = self.standby.lock().unwrap_or_else(PoisonError::into_inner);
+        if standby.stale {
+            standby.tree = Arc::new(copy_of(&self.snapshot()));
+            standby.pending = None;
+        }
+
+        standby.stale = true;
+        let pending = standby.pending.take();
+        let tree = writable(&mut standby.tree);
+        if let Some((pending_pos, pending_value)) = pending {
+            tree.modify(pending_pos, &pending_value)?;
+        }
+        let result = tree.modify(pos, value);
+        standby.stale = false;
+        result?;
+
+        let mut published = self
+            .published
+            .write()
+            .unwrap_or_else(PoisonError::into_inner);
+        mem::swap(&mut *published, &mut standby.tree);
+        standby.pending = Some((pos, value.clone()));
+
+        Ok(())
+    }
+}
+
+impl<I, O, C> From<SegmentTree<I, O, C>> for ConcurrentSegmentTree<I, O, C>
+where
+    O: Clone,
+    C: SegmentTreeComputation,
+{
+    fn from(tree: SegmentTree<I, O, C>) -> Self {
+        Self {
+            standby: Mutex::new(Standby {
+                tree: Arc::new(copy_of(&tree)),
+                pending: None,
+                stale: false,
+            }),
+            len: tree.len,
+            published: RwLock::new(Arc::new(tree)),
+        }
+    }
+}
+
+/// Returns the tree behind `tree`, copying it first if a reader still holds it.
+fn writable<I, O, C>(tree: &mut Arc<SegmentTree<I, O, C>>) -> &mut SegmentTree<I, O, C>
+where
+    O: Clone,
+    C: SegmentTreeComputation,
+{
+    if Arc::get_mut(tree).is_none() {
+        *tree = Arc::new(copy_of(tree));
+    }
+
+    Arc::get_mut(tree).expect("Copied tree is not shared")
+}
+
+/// Copies the nodes and invariant check of `tree`, with an empty journal and
+/// fresh statistics.
+fn copy_of<I, O, C>(tree: &SegmentTree<I, O, C>) -> SegmentTree<I, O, C>
+where
+    O: Clone,
+    C: SegmentTreeComputation,
+{
+    SegmentTree {
+        data: tree.data.clone(),
+        len: tree.len,
+        journal: Journal::default(),
+        counters: Counters::from_build(&DefaultTracer::default()),
+        invariants: tree.invariants,
+        phantom: PhantomData,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{
+        ConcurrentMaxSliceSumSegmentTree, ConcurrentSegmentTree, ConcurrentSumSegmentTree,
+    };
+    use crate::{
+        computation::{MaxSliceSum, SegmentTreeComputation},
+        SegmentTreeError,
+    };
+    use std::{
+        panic::{self, AssertUnwindSafe},
+        sync::{
+            atomic::{AtomicBool, Ordering},
+            mpsc, Arc, Mutex,
+        },
+        thread,
+        time::Duration,
+    };
+
+    struct PanickingSum;
+
+    impl SegmentTreeComputation for PanickingSum {
+        type Input = i32;
+        type Output = i32;
+
+        fn combine(left_result: &i32, right_result: &i32) -> i32 {
+            left_result
+                .checked_add(*right_result)
+                .expect("Sum fits in an i32")
+        }
+
+        fn update(_: &i32, new_value: &i32) -> i32 {
+            *new_value
+        }
+
+        fn init(value: &i32) -> i32 {
+            *value
+        }
+    }
+
+    /// Sum whose `update` to `GatedSum::BLOCKING` waits until `GATE` is free.
+    struct GatedSum;
+
+    static GATE: Mutex<()> = Mutex::new(());
+    static ENTERED: AtomicBool = AtomicBool::new(false);
+
+    impl GatedSum {
+        const BLOCKING: i32 = 100;
+    }
+
+    impl SegmentTreeComputation for GatedSum {
+        type Input = i32;
+        type Output = i32;
+
+        fn combine(left_result: &i32, right_result: &i32) -> i32 {
+            left_result + right_result
+        }
+
+        fn update(_: &i32, new_value: &i32) -> i32 {
+            if *new_value == Self::BLOCKING {
+                ENTERED.store(true, Ordering::SeqCst);
+                drop(GATE.lock().unwrap());
+            }
+            *new_value
+        }
+
+        fn init(value: &i32) -> i32 {
+            *value
+        }
+    }
+
+    #[test]
+    fn test_get_and_modify() {
+        let mut arr = [-2, 1, 2, 1, -10, 7, 2, -11, 4];
+        let tree = ConcurrentMaxSliceSumSegmentTree::build(&arr);
+
+        assert!(!tree.is_empty());
+        assert_eq!(tree.len(), arr.len());
+
+        for (pos, value) in [(4, 10), (7, 0), (0, 5)] {
+            arr[pos] = value;
+            assert_eq!(tree.modify(pos, &value), Ok(()));
+
+            for left in 0..arr.len() {
+                for right in left..arr.len() {
+                    let expected = Ok(MaxSliceSum::from_slice(&arr[left..=right]));
+
+                    assert_eq!(tree.get(left, right), expected);
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_errors() {
+        let tree = ConcurrentSumSegmentTree::build(&[1, 3, 7]);
+
+        assert_eq!(
+            tree.get(2, 1),
+            Err(SegmentTreeError::InvalidRange { left: 2, right: 1 })
+        );
+        assert_eq!(
+            tree.get(0, 3),
+            Err(SegmentTreeError::OutOfBounds { index: 3, len: 3 })
+        );
+        assert_eq!(
+            tree.modify(3, &1),
+            Err(SegmentTreeError::OutOfBounds { index: 3, len: 3 })
+        );
+
+        let empty = ConcurrentSumSegmentTree::<i32>::build(&[]);
+        assert!(empty.is_empty());
+        assert_eq!(
+            empty.get(0, 0),
+            Err(SegmentTreeError::OutOfBounds { index: 0, len: 0 })
+        );
+    }
+
+    #[test]
+    fn test_panicking_combine() {
+        let tree = ConcurrentSegmentTree::<i32, i32, PanickingSum>::build(&[1, 3, 7]);
+
+        let result = panic::catch_unwind(AssertUnwindSafe(|| tree.modify(2, &i32::MAX)));
+        assert!(result.is_err());
+        assert_eq!(tree.get(0, 2), Ok(11));
+        assert_eq!(tree.get(2, 2), Ok(7));
+
+        assert_eq!(tree.modify(2, &-4), Ok(()));
+        assert_eq!(tree.get(0, 2), Ok(0));
+    }
+
+    #[test]
+    fn test_reader_progresses_during_modify() {
+        let tree = ConcurrentSegmentTree::<i32, i32, GatedSum>::build(&[1, 3, 7]);
+        let tree = Arc::new(tree);
+
+        let gate = GATE.lock().unwrap();
+        let writer = {
+            let tree = Arc::clone(&tree);
+            thread::spawn(move || tree.modify(1, &GatedSum::BLOCKING))
+        };
+        while !ENTERED.load(Ordering::SeqCst) {
+            thread::yield_now();
+        }
+
+        let (sender, receiver) = mpsc::channel();
+        let reader = {
+            let tree = Arc::clone(&tree);
+            thread::spawn(move || sender.send(tree.get(0, 2)).unwrap())
+        };
+        let result = receiver.recv_timeout(Duration::from_secs(10));
+        drop(gate);
+
+        assert_eq!(result, Ok(Ok(11)));
+        assert_eq!(writer.join().unwrap(), Ok(()));
+        reader.join().unwrap();
+        assert_eq!(tree.get(0, 2), Ok(108));
+        assert_eq!(tree.modify(0, &2), Ok(()));
+        assert_eq!(tree.get(0, 2), Ok(109));
+    }
+
+    #[test]
+    fn test_concurrent_readers_and_writers() {
+        const LEN: usize = 1000;
+        const WRITERS: usize = 4;
+        const ROUNDS: i64 = 5;
+
+        let tree = Arc::new(ConcurrentSumSegmentTree::build(&[0i64; LEN]));
+
+        let writers: Vec<_> = (0..WRITERS)
+            .map(|writer| {
+                let tree = Arc::clone(&tree);
+                thread::spawn(move || {
+                    for round in 1..=ROUNDS {
+                        for pos in (writer..LEN).step_by(WRITERS) {
+                            tree.modify(pos, &round).unwrap();
+                        }
+                    }
+                })
+            })
+            .collect();
+        let readers: Vec<_> = (0..4)
+            .map(|reader| {
+                let tree = Arc::clone(&tree);
+                thread::spawn(move || {
+                    let mut prev_sum = 0;
+                    for iteration in 0..2000 {
+                        let left = (reader * 97 + iteration * 13) % LEN;
+                        let sum = tree.get(0, LEN - 1).unwrap();
+                        let part = tree.get(left, LEN - 1).unwrap();
+
+                        // Values only grow and stay within [0, ROUNDS].
+                        assert!(sum >= prev_sum);
+                        assert!(sum <= ROUNDS * LEN as i64);
+                        assert!((0..=ROUNDS * (LEN - left) as i64).contains(&part));
+                        prev_sum = sum;
+                    }
+                })
+            })
+            .collect();
+
+        for handle in writers.into_iter().chain(readers) {
+            handle.join().unwrap();
+        }
+
+        assert_eq!(tree.get(0, LEN - 1), Ok(ROUNDS * LEN as i64));
+    }
+
+    #[test]
+    fn test_snapshot_consistency() {
+        const LEN: usize = 64;
+
+        // Each modify moves one unit between neighbours, so any linearizable
+        // read of the whole range sees the same total.
+        let tree = Arc::new(ConcurrentSumSegmentTree::build(&[1i64; LEN]));
+        let writer = {
+            let tree = Arc::clone(&tree);
+            thread::spawn(move || {
+                for step in 0..5000 {
+                    let pos = step % (LEN - 1);
+                    let (a, b) = (
+                        tree.get(pos, pos).unwrap(),
+                        tree.get(pos + 1, pos + 1).unwrap(),
+                    );
+                    tree.modify(pos, &(a - 1)).unwrap();
+                    tree.modify(pos + 1, &(b + 1)).unwrap();
+                    tree.modify(pos + 1, &b).unwrap();
+                    tree.modify(pos, &a).unwrap();
+                }
+            })
+        };
+
+        let readers: Vec<_> = (0..3)
+            .map(|_| {
+                let tree = Arc::clone(&tree);
+                thread::spawn(move || {
+                    for _ in 0..5000 {
+                        let sum = tree.get(0, LEN - 1).unwrap();
+                        assert!(sum == LEN as i64 || sum == LEN as i64 - 1);
+                    }
+                })
+            })
+            .collect();
+
+        writer.join().unwrap();
+        for reader in readers {
+            reader.join().unwrap();
+        }
+    }
+}
@@ -0,0 +1,207 @@
+use super::{Layout, SegmentTree};
+use crate::{
+    computation::SegmentTreeComputation,
+    errors::{SegmentTreeError, SegmentTreeResult},
+};
+use core::fmt;
+#[cfg(not(feature = "debug-invariants"))]
+use core::marker::PhantomData;
+
+/// Validation a tree runs after every mutation with the `debug-invariants`
+/// feature, unless `skip_invariant_checks` was called on it. It is stored as a
+/// function pointer so the mutating methods need no `PartialEq` bound, and
+/// compiles away without the feature.
+#[cfg(feature = "debug-invariants")]
+pub(super) struct InvariantCheck<T>(Option<fn(&T) -> SegmentTreeResult<()>>);
+
+#[cfg(feature = "debug-invariants")]
+impl<T> InvariantCheck<T> {
+    pub(super) fn new(validate: fn(&T) -> SegmentTreeResult<()>) -> Self {
+        Self(Some(validate))
+    }
+
+    fn disable(&mut self) {
+        self.0 = None;
+    }
+
+    /// Panics if `operation` left `tree` inconsistent.
+    pub(super) fn run(&self, tree: &T, operation: fmt::Arguments<'_>) {
+        if let Some(Err(error)) = self.0.map(|validate| validate(tree)) {
+            panic!("Segment tree invariant violated after {operation}: {error}");
+        }
+    }
+}
+
+#[cfg(not(feature = "debug-invariants"))]
+pub(super) struct InvariantCheck<T>(PhantomData<fn(&T)>);
+
+#[cfg(not(feature = "debug-invariants"))]
+impl<T> InvariantCheck<T> {
+    #[inline(always)]
+    pub(super) fn new(_: fn(&T) -> SegmentTreeResult<()>) -> Self {
+        Self(PhantomData)
+    }
+
+    #[inline(always)]
+    fn disable(&mut self) {}
+
+    #[inline(always)]
+    pub(super) fn run(&self, _: &T, _: fmt::Arguments<'_>) {}
+}
+
+impl<T> Clone for InvariantCheck<T> {
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl<T> Copy for InvariantCheck<T> {}
+
+impl<I, O, C, L> SegmentTree<I, O, C, L>
+where
+    C: SegmentTreeComputation<Input = I, Output = O>,
+{
+    /// Stops this tree from validating itself after every `modify`,
+    /// `modify_traced` and `rollback_to` under the `debug-invariants` feature,
+    /// e.g. for a large tree in an otherwise checked build.
+    pub fn skip_invariant_checks(&mut self) {
+        self.invariants.disable();
+    }
+}
+
+impl<I, O, C, L> SegmentTree<I, O, C, L>
+where
+    O: Default + Clone + Copy + PartialEq,
+    C: SegmentTreeComputation<Input = I, Output = O>,
+    L: Layout,
+{
+    /// Checks that every internal node equals `C::combine` of its children.
+    /// Children are checked before their parent, so the reported node is the
+    /// deepest inconsistent one on the leftmost bad path.
+    pub fn validate(&self) -> SegmentTreeResult<()> {
+        if self.is_empty() {
+            Ok(())
+        } else {
+            self.internal_validate(1, 0, self.len - 1)
+        }
+    }
+
+    fn internal_validate(
+        &self,
+        index: usize,
+        cur_left: usize,
+        cur_right: usize,
+    ) -> SegmentTreeResult<()> {
+        if cur_left == cur_right {
+            return Ok(());
+        }
+
+        let mid = (cur_left + cur_right) / 2;
+        self.internal_validate(index * 2, cur_left, mid)?;
+        self.internal_validate(index * 2 + 1, mid + 1, cur_right)?;
+
+        let expected = C::combine(
+            self.node(index * 2, cur_left, mid),
+            self.node(index * 2 + 1, mid + 1, cur_right),
+        );
+
+        if *self.node(index, cur_left, cur_right) == expected {
+            Ok(())
+        } else {
+            Err(SegmentTreeError::InvalidNode {
+                index,
+                left: cur_left,
+                right: cur_right,
+            })
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{MaxSliceSumSegmentTree, SegmentTreeError, SumSegmentTree};
+
+    #[test]
+    fn test_validate() {
+        let mut tree = SumSegmentTree::build(&[1, 3, 7, 27, 5]);
+
+        assert_eq!(tree.validate(), Ok(()));
+        assert_eq!(tree.modify(2, &-4), Ok(()));
+        assert_eq!(tree.validate(), Ok(()));
+
+        tree.data[5] = 100;
+        assert_eq!(
+            tree.validate(),
+            Err(SegmentTreeError::InvalidNode {
+                index: 2,
+                left: 0,
+                right: 2
+            })
+        );
+
+        tree.data[2] = tree.data[4] + tree.data[5];
+        assert_eq!(
+            tree.validate(),
+            Err(SegmentTreeError::InvalidNode {
+                index: 1,
+                left: 0,
+                right: 4
+            })
+        );
+
+        let empty = MaxSliceSumSegmentTree::<i64>::build(&[]);
+        assert_eq!(empty.validate(), Ok(()));
+    }
+
+    #[cfg(feature = "debug-invariants")]
+    #[test]
+    #[should_panic(expected = "invariant violated")]
+    fn test_debug_invariants() {
+        let mut tree = SumSegmentTree::build(&[1, 3, 7, 27]);
+
+        tree.data[3] = 0;
+        let _ = tree.modify(0, &2);
+    }
+
+    #[cfg(feature = "debug-invariants")]
+    #[test]
+    #[should_panic(expected = "invariant violated after rolling back")]
+    fn test_debug_invariants_rollback() {
+        let mut tree = SumSegmentTree::build(&[1, 3, 7, 27]);
+
+        let checkpoint = tree.checkpoint();
+        assert_eq!(tree.modify(0, &2), Ok(()));
+        tree.data[3] = 0;
+        let _ = tree.rollback_to(checkpoint);
+    }
+
+    #[cfg(all(feature = "debug-invariants", feature = "trace"))]
+    #[test]
+    #[should_panic(expected = "invariant violated after modifying 0")]
+    fn test_debug_invariants_traced() {
+        let mut tree = SumSegmentTree::build(&[1, 3, 7, 27]);
+
+        tree.data[3] = 0;
+        let _ = tree.modify_traced(0, &2);
+    }
+
+    #[test]
+    fn test_skip_invariant_checks() {
+        let mut tree = SumSegmentTree::build(&[1, 3, 7, 27]);
+        tree.skip_invariant_checks();
+
+        tree.data[3] = 0;
+        assert_eq!(tree.modify(0, &2), Ok(()));
+        assert!(tree.validate().is_err());
+    }
+
+    #[cfg(not(feature = "debug-invariants"))]
+    #[test]
+    fn test_unchecked_without_feature() {
+        let mut tree = SumSegmentTree::build(&[1, 3, 7, 27]);
+
+        tree.data[3] = 0;
+        assert_eq!(tree.modify(0, &2), Ok(()));
+        assert!(tree.validate().is_err());
+    }
+}
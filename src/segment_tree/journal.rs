@@ -0,0 +1,186 @@
+use super::{Layout, SegmentTree};
+use crate::{
+    computation::SegmentTreeComputation,
+    errors::{SegmentTreeError, SegmentTreeResult},
+};
+use alloc::{vec, vec::Vec};
+
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub struct Checkpoint {
+    id: usize,
+}
+
+/// Previous values of the nodes overwritten by `modify` since the first live
+/// checkpoint, in the order they were overwritten.
+pub(super) struct Journal<O> {
+    next_id: usize,
+    /// Live checkpoints, oldest first, with the number of entries at the time
+    /// they were taken.
+    checkpoints: Vec<(usize, usize)>,
+    entries: Vec<(usize, O)>,
+}
+
+impl<O> Default for Journal<O> {
+    fn default() -> Self {
+        Self {
+            next_id: 0,
+            checkpoints: vec![],
+            entries: vec![],
+        }
+    }
+}
+
+impl<O: Clone> Journal<O> {
+    pub(super) fn record(&mut self, index: usize, prev_value: &O) {
+        if !self.checkpoints.is_empty() {
+            self.entries.push((index, prev_value.clone()));
+        }
+    }
+}
+
+impl<I, O, C, L> SegmentTree<I, O, C, L>
+where
+    O: Default + Clone + Copy,
+    C: SegmentTreeComputation<Input = I, Output = O>,
+    L: Layout,
+{
+    /// Starts recording modifications, if needed, and returns a checkpoint to
+    /// roll them back to.
+    pub fn checkpoint(&mut self) -> Checkpoint {
+        let id = self.journal.next_id;
+        self.journal.next_id += 1;
+        self.journal
+            .checkpoints
+            .push((id, self.journal.entries.len()));
+
+        Checkpoint { id }
+    }
+
+    /// Undoes every modification made since `checkpoint` was taken and
+    /// invalidates the checkpoints taken after it.
+    ///
+    /// `checkpoint` itself stays live, so modifications keep being recorded
+    /// until `commit` is called.
+    pub fn rollback_to(&mut self, checkpoint: Checkpoint) -> SegmentTreeResult<()> {
+        let live = self
+            .journal
+            .checkpoints
+            .iter()
+            .rposition(|&(id, _)| id == checkpoint.id)
+            .ok_or(SegmentTreeError::InvalidCheckpoint)?;
+        let (_, position) = self.journal.checkpoints[live];
+        self.journal.checkpoints.truncate(live + 1);
+
+        for (index, prev_value) in self.journal.entries.drain(position..).rev() {
+            self.data[index] = prev_value;
+        }
+        self.invariants
+            .run(self, format_args!("rolling back to {checkpoint:?}"));
+
+        Ok(())
+    }
+
+    /// Keeps every modification, invalidates all checkpoints and stops
+    /// recording.
+    pub fn commit(&mut self) {
+        self.journal.entries.clear();
+        self.journal.checkpoints.clear();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{MaxSliceSumSegmentTree, SegmentTreeError, SumSegmentTree};
+
+    #[test]
+    fn test_rollback() {
+        let arr = [1, 3, 7, 27];
+        let mut tree = SumSegmentTree::build(&arr);
+        let original = tree.data.clone();
+
+        let checkpoint = tree.checkpoint();
+        assert_eq!(tree.modify(3, &10), Ok(()));
+        assert_eq!(tree.modify(1, &73), Ok(()));
+        assert_eq!(tree.get(0, 3), Ok(91));
+        assert_eq!(tree.journal.entries.len(), 6);
+
+        assert_eq!(tree.rollback_to(checkpoint), Ok(()));
+        assert_eq!(tree.data, original);
+        assert!(tree.journal.entries.is_empty());
+    }
+
+    #[test]
+    fn test_nested_checkpoints() {
+        let arr = [-2, 1, 2, 1, -10, 7, 2, -11, 4];
+        let mut tree = MaxSliceSumSegmentTree::build(&arr);
+        let original = tree.data.clone();
+
+        let outer = tree.checkpoint();
+        assert_eq!(tree.modify(4, &10), Ok(()));
+        let after_first = tree.data.clone();
+
+        let inner = tree.checkpoint();
+        assert_eq!(tree.modify(7, &0), Ok(()));
+        assert_eq!(tree.modify(0, &5), Ok(()));
+
+        assert_eq!(tree.rollback_to(inner), Ok(()));
+        assert_eq!(tree.data, after_first);
+
+        assert_eq!(tree.modify(8, &-4), Ok(()));
+        assert_eq!(tree.rollback_to(outer), Ok(()));
+        assert_eq!(tree.data, original);
+
+        assert_eq!(
+            tree.rollback_to(inner),
+            Err(SegmentTreeError::InvalidCheckpoint)
+        );
+    }
+
+    #[test]
+    fn test_rollback_past_checkpoint() {
+        let arr = [1, 3, 7, 27];
+        let mut tree = SumSegmentTree::build(&arr);
+
+        let outer = tree.checkpoint();
+        assert_eq!(tree.modify(0, &-8), Ok(()));
+        let inner = tree.checkpoint();
+        assert_eq!(tree.get(0, 3), Ok(29));
+
+        assert_eq!(tree.rollback_to(outer), Ok(()));
+        assert_eq!(tree.modify(1, &0), Ok(()));
+        assert_eq!(tree.modify(2, &2), Ok(()));
+        assert_eq!(
+            tree.rollback_to(inner),
+            Err(SegmentTreeError::InvalidCheckpoint)
+        );
+        assert_eq!(tree.get(0, 3), Ok(30));
+
+        assert_eq!(tree.rollback_to(outer), Ok(()));
+        assert_eq!(tree.get(0, 3), Ok(38));
+    }
+
+    #[test]
+    fn test_commit() {
+        let arr = [1, 3, 7, 27];
+        let mut tree = SumSegmentTree::build(&arr);
+
+        let checkpoint = tree.checkpoint();
+        assert_eq!(tree.modify(0, &5), Ok(()));
+        tree.commit();
+
+        assert!(tree.journal.entries.is_empty());
+        assert_eq!(
+            tree.rollback_to(checkpoint),
+            Err(SegmentTreeError::InvalidCheckpoint)
+        );
+        assert_eq!(tree.get(0, 3), Ok(42));
+
+        assert_eq!(tree.modify(1, &0), Ok(()));
+        assert!(tree.journal.entries.is_empty());
+
+        let checkpoint = tree.checkpoint();
+        assert_eq!(tree.modify(2, &0), Ok(()));
+        assert_eq!(tree.rollback_to(checkpoint), Ok(()));
+        assert_eq!(tree.get(0, 3), Ok(39));
+    }
+}
@@ -0,0 +1,190 @@
+/// Maps tree nodes to slots of the backing `Vec`.
+///
+/// Nodes are identified the way the traversals see them: `index` is the heap
+/// index (root `1`, children `2 * index` and `2 * index + 1`) of the node
+/// covering `[left, right]` in a tree over `len` elements.
+pub trait Layout {
+    fn capacity(len: usize) -> usize;
+
+    fn position(index: usize, left: usize, right: usize, len: usize) -> usize;
+}
+
+/// Stores node `index` at slot `index`, using `4 * len` slots.
+#[derive(Debug, Default, Clone, Copy)]
+pub struct HeapLayout;
+
+impl Layout for HeapLayout {
+    fn capacity(len: usize) -> usize {
+        len * 4
+    }
+
+    #[inline]
+    fn position(index: usize, _: usize, _: usize, _: usize) -> usize {
+        index
+    }
+}
+
+/// Uses exactly `2 * len - 1` slots. The node covering `[left, right]` lives at
+/// `(left + right) | (left != right)`: a leaf takes the even slot `2 * left`
+/// and an inner node the odd slot right after the last leaf of its left half.
+#[derive(Debug, Default, Clone, Copy)]
+pub struct CompactLayout;
+
+impl Layout for CompactLayout {
+    fn capacity(len: usize) -> usize {
+        (len * 2).saturating_sub(1)
+    }
+
+    #[inline]
+    fn position(_: usize, left: usize, right: usize, _: usize) -> usize {
+        (left + right) | usize::from(left != right)
+    }
+}
+
+/// Blocked van Emde Boas order: the top half of the levels is stored first,
+/// followed by each bottom subtree, recursively, so a root-to-leaf path
+/// touches `O(log_B n)` cache lines for any block size `B`. Positions are
+/// recomputed per node in `O(log log n)`, which the benchmarks show costs more
+/// than it saves on trees that fit in memory comfortably.
+#[derive(Debug, Default, Clone, Copy)]
+pub struct VebLayout;
+
+impl VebLayout {
+    fn height(len: usize) -> u32 {
+        len.next_power_of_two().trailing_zeros() + 1
+    }
+
+    fn veb_position(mut index: usize, mut height: u32) -> usize {
+        let mut position = 0;
+
+        while height > 1 {
+            let depth = index.ilog2();
+            let top = height / 2;
+            let bottom = height - top;
+
+            if depth < top {
+                height = top;
+            } else {
+                let shift = depth - top;
+                let subtree = (index >> shift) - (1 << top);
+
+                position += (1 << top) - 1 + subtree * ((1 << bottom) - 1);
+                index = (1 << shift) | (index & ((1 << shift) - 1));
+                height = bottom;
+            }
+        }
+
+        position
+    }
+}
+
+impl Layout for VebLayout {
+    fn capacity(len: usize) -> usize {
+        if len == 0 {
+            0
+        } else {
+            (1 << Self::height(len)) - 1
+        }
+    }
+
+    #[inline]
+    fn position(index: usize, _: usize, _: usize, len: usize) -> usize {
+        Self::veb_position(index, Self::height(len))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{CompactLayout, HeapLayout, Layout, VebLayout};
+    use crate::{computation::MaxSliceSumComputation, SegmentTree, SumSegmentTree};
+
+    fn collect_positions<L: Layout>(len: usize) -> Vec<usize> {
+        let mut positions = vec![];
+        let mut stack = vec![(1, 0, len - 1)];
+
+        while let Some((index, left, right)) = stack.pop() {
+            positions.push(L::position(index, left, right, len));
+
+            if left != right {
+                let mid = (left + right) / 2;
+                stack.push((index * 2, left, mid));
+                stack.push((index * 2 + 1, mid + 1, right));
+            }
+        }
+
+        positions
+    }
+
+    fn assert_injective<L: Layout>() {
+        for len in 1..200 {
+            let mut positions = collect_positions::<L>(len);
+            assert_eq!(positions.len(), 2 * len - 1);
+            assert!(positions
+                .iter()
+                .all(|&position| position < L::capacity(len)));
+
+            positions.sort_unstable();
+            positions.dedup();
+            assert_eq!(positions.len(), 2 * len - 1, "collision for len {len}");
+        }
+    }
+
+    #[test]
+    fn test_positions_are_injective() {
+        assert_injective::<HeapLayout>();
+        assert_injective::<CompactLayout>();
+        assert_injective::<VebLayout>();
+
+        assert_eq!(CompactLayout::capacity(0), 0);
+        assert_eq!(VebLayout::capacity(0), 0);
+        assert_eq!(CompactLayout::capacity(5), 9);
+    }
+
+    #[test]
+    fn test_veb_order() {
+        // Height 4: the top two levels come first, then the four bottom subtrees.
+        let order: Vec<usize> = (1..16)
+            .map(|index| VebLayout::veb_position(index, 4))
+            .collect();
+
+        assert_eq!(
+            order,
+            vec![0, 1, 2, 3, 6, 9, 12, 4, 5, 7, 8, 10, 11, 13, 14]
+        );
+    }
+
+    #[test]
+    fn test_layouts_agree() {
+        let mut arr = vec![-2i64, 1, 2, 1, -10, 7, 2, -11, 4, 5, -3];
+        let mut heap = SegmentTree::<_, _, MaxSliceSumComputation<i64>>::build(&arr);
+        let mut compact =
+            SegmentTree::<_, _, MaxSliceSumComputation<i64>, _>::build_in(&arr, CompactLayout);
+        let mut veb =
+            SegmentTree::<_, _, MaxSliceSumComputation<i64>, _>::build_in(&arr, VebLayout);
+
+        assert_eq!(compact.data.len(), 2 * arr.len() - 1);
+        assert_eq!(veb.data.len(), 31);
+
+        for (pos, value) in [(3, 9), (10, -20), (0, 6), (5, -1)] {
+            arr[pos] = value;
+            assert_eq!(heap.modify(pos, &value), Ok(()));
+            assert_eq!(compact.modify(pos, &value), Ok(()));
+            assert_eq!(veb.modify(pos, &value), Ok(()));
+
+            for left in 0..arr.len() {
+                for right in left..arr.len() {
+                    let expected = heap.get(left, right);
+
+                    assert_eq!(compact.get(left, right), expected);
+                    assert_eq!(veb.get(left, right), expected);
+                }
+            }
+        }
+
+        assert_eq!(compact.validate(), Ok(()));
+        assert_eq!(veb.validate(), Ok(()));
+
+        let single = SumSegmentTree::build_in(&[7i64], CompactLayout);
+        assert_eq!(single.get(0, 0), Ok(7));
+    }
+}
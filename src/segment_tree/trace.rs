@@ -0,0 +1,346 @@
+/// Hooks called by the tree traversals. With the `trace` feature disabled the
+/// traversals use `NoopTracer`, so the instrumentation compiles away.
+pub(crate) trait Tracer: Default {
+    /// Called when a traversal reaches the node at heap `index`.
+    fn enter(&mut self, index: usize);
+
+    fn exit(&mut self);
+
+    fn combine(&mut self);
+}
+
+#[derive(Default)]
+pub(crate) struct NoopTracer;
+
+impl Tracer for NoopTracer {
+    #[inline(always)]
+    fn enter(&mut self, _: usize) {}
+
+    #[inline(always)]
+    fn exit(&mut self) {}
+
+    #[inline(always)]
+    fn combine(&mut self) {}
+}
+
+#[cfg(not(feature = "trace"))]
+pub(super) type DefaultTracer = NoopTracer;
+
+#[cfg(not(feature = "trace"))]
+#[derive(Default)]
+pub(super) struct Counters;
+
+#[cfg(not(feature = "trace"))]
+impl Counters {
+    #[inline(always)]
+    pub(super) fn from_build(_: &DefaultTracer) -> Self {
+        Self
+    }
+
+    #[inline(always)]
+    pub(super) fn record_get(&self, _: &DefaultTracer) {}
+
+    #[inline(always)]
+    pub(super) fn record_modify(&self, _: &DefaultTracer) {}
+}
+
+#[cfg(all(feature = "trace", not(target_has_atomic = "ptr")))]
+compile_error!("The `trace` feature needs pointer-sized atomics on the target");
+
+#[cfg(feature = "trace")]
+pub use enabled::{OperationStatistics, Statistics, Trace};
+
+#[cfg(feature = "trace")]
+pub(super) use enabled::{Counters, DefaultTracer};
+
+#[cfg(feature = "trace")]
+mod enabled {
+    use super::{
+        super::{traversal, HeapLayout, Layout, SegmentTree},
+        Tracer,
+    };
+    use crate::{computation::SegmentTreeComputation, errors::SegmentTreeResult};
+    use core::sync::atomic::{AtomicUsize, Ordering::Relaxed};
+
+    #[derive(Debug, Default, PartialEq, Eq, Clone, Copy)]
+    pub struct Trace {
+        nodes_visited: usize,
+        combine_calls: usize,
+        max_depth: usize,
+        depth: usize,
+    }
+
+    impl Trace {
+        pub fn nodes_visited(&self) -> usize {
+            self.nodes_visited
+        }
+
+        pub fn combine_calls(&self) -> usize {
+            self.combine_calls
+        }
+
+        pub fn max_depth(&self) -> usize {
+            self.max_depth
+        }
+    }
+
+    impl Tracer for Trace {
+        fn enter(&mut self, _: usize) {
+            self.nodes_visited += 1;
+            self.depth += 1;
+            self.max_depth = self.max_depth.max(self.depth);
+        }
+
+        fn exit(&mut self) {
+            self.depth -= 1;
+        }
+
+        fn combine(&mut self) {
+            self.combine_calls += 1;
+        }
+    }
+
+    pub(in crate::segment_tree) type DefaultTracer = Trace;
+
+    #[derive(Debug, Default, PartialEq, Eq, Clone, Copy)]
+    pub struct OperationStatistics {
+        calls: usize,
+        nodes_visited: usize,
+        combine_calls: usize,
+        max_depth: usize,
+    }
+
+    impl OperationStatistics {
+        pub fn calls(&self) -> usize {
+            self.calls
+        }
+
+        pub fn nodes_visited(&self) -> usize {
+            self.nodes_visited
+        }
+
+        pub fn combine_calls(&self) -> usize {
+            self.combine_calls
+        }
+
+        pub fn max_depth(&self) -> usize {
+            self.max_depth
+        }
+    }
+
+    #[derive(Debug, Default, PartialEq, Eq, Clone, Copy)]
+    pub struct Statistics {
+        build: OperationStatistics,
+        get: OperationStatistics,
+        modify: OperationStatistics,
+    }
+
+    impl Statistics {
+        pub fn build(&self) -> OperationStatistics {
+            self.build
+        }
+
+        pub fn get(&self) -> OperationStatistics {
+            self.get
+        }
+
+        pub fn modify(&self) -> OperationStatistics {
+            self.modify
+        }
+    }
+
+    #[derive(Default)]
+    struct OperationCounters {
+        calls: AtomicUsize,
+        nodes_visited: AtomicUsize,
+        combine_calls: AtomicUsize,
+        max_depth: AtomicUsize,
+    }
+
+    impl OperationCounters {
+        fn record(&self, trace: &Trace) {
+            self.calls.fetch_add(1, Relaxed);
+            self.nodes_visited.fetch_add(trace.nodes_visited, Relaxed);
+            self.combine_calls.fetch_add(trace.combine_calls, Relaxed);
+            self.max_depth.fetch_max(trace.max_depth, Relaxed);
+        }
+
+        fn snapshot(&self) -> OperationStatistics {
+            OperationStatistics {
+                calls: self.calls.load(Relaxed),
+                nodes_visited: self.nodes_visited.load(Relaxed),
+                combine_calls: self.combine_calls.load(Relaxed),
+                max_depth: self.max_depth.load(Relaxed),
+            }
+        }
+    }
+
+    /// Cumulative counters of a tree. Atomics keep `get` usable through `&self`
+    /// from several threads.
+    #[derive(Default)]
+    pub(in crate::segment_tree) struct Counters {
+        build: OperationCounters,
+        get: OperationCounters,
+        modify: OperationCounters,
+    }
+
+    impl Counters {
+        pub(in crate::segment_tree) fn from_build(trace: &Trace) -> Self {
+            let counters = Self::default();
+            counters.build.record(trace);
+            counters
+        }
+
+        pub(in crate::segment_tree) fn record_get(&self, trace: &Trace) {
+            self.get.record(trace);
+        }
+
+        pub(in crate::segment_tree) fn record_modify(&self, trace: &Trace) {
+            self.modify.record(trace);
+        }
+    }
+
+    impl<I, O, C> SegmentTree<I, O, C>
+    where
+        O: Default + Clone + Copy + PartialEq,
+        C: SegmentTreeComputation<Input = I, Output = O>,
+    {
+        pub fn build_traced(arr: &[I]) -> (Self, Trace) {
+            Self::build_traced_in(arr, HeapLayout)
+        }
+    }
+
+    impl<I, O, C, L> SegmentTree<I, O, C, L>
+    where
+        O: Default + Clone + Copy,
+        C: SegmentTreeComputation<Input = I, Output = O>,
+        L: Layout,
+    {
+        pub fn build_traced_in(arr: &[I], _layout: L) -> (Self, Trace)
+        where
+            O: PartialEq,
+        {
+            let mut trace = Trace::default();
+            let tree = Self::from_input(arr, &mut trace);
+
+            (tree, trace)
+        }
+
+        pub fn get_traced(&self, left: usize, right: usize) -> SegmentTreeResult<(O, Trace)> {
+            self.check_range(left, right)?;
+
+            let mut trace = Trace::default();
+            let result = traversal::fold(self, 1, 0, self.len - 1, left, right, &mut trace);
+            self.counters.record_get(&trace);
+
+            Ok((result, trace))
+        }
+
+        pub fn modify_traced(&mut self, pos: usize, value: &I) -> SegmentTreeResult<Trace> {
+            self.check_pos(pos)?;
+
+            let mut trace = Trace::default();
+            self.internal_modify(1, 0, self.len - 1, pos, value, &mut trace);
+            self.counters.record_modify(&trace);
+            self.invariants.run(self, format_args!("modifying {pos}"));
+
+            Ok(trace)
+        }
+
+        pub fn statistics(&self) -> Statistics {
+            Statistics {
+                build: self.counters.build.snapshot(),
+                get: self.counters.get.snapshot(),
+                modify: self.counters.modify.snapshot(),
+            }
+        }
+
+        pub fn reset_statistics(&mut self) {
+            self.counters = Counters::default();
+        }
+    }
+
+    #[cfg(test)]
+    mod tests {
+        use crate::{CompactLayout, SegmentTreeError, SumSegmentTree};
+
+        #[test]
+        fn test_build_trace() {
+            let (tree, trace) = SumSegmentTree::build_traced(&[1, 3, 7, 27, 5]);
+
+            assert_eq!(tree.get(0, 4), Ok(43));
+            assert_eq!(trace.nodes_visited(), 9);
+            assert_eq!(trace.combine_calls(), 4);
+            assert_eq!(trace.max_depth(), 4);
+            assert_eq!(tree.statistics().build().nodes_visited(), 9);
+
+            let (tree, trace) = SumSegmentTree::build_traced_in(&[1, 3, 7], CompactLayout);
+            assert_eq!(tree.get(0, 2), Ok(11));
+            assert_eq!(trace.nodes_visited(), 5);
+            assert_eq!(trace.combine_calls(), 2);
+            assert_eq!(trace.max_depth(), 3);
+        }
+
+        #[test]
+        fn test_get_trace() {
+            let tree = SumSegmentTree::build(&[1, 3, 7, 27]);
+
+            let (result, trace) = tree.get_traced(0, 3).unwrap();
+            assert_eq!(result, 38);
+            assert_eq!(trace.nodes_visited(), 1);
+            assert_eq!(trace.combine_calls(), 0);
+            assert_eq!(trace.max_depth(), 1);
+
+            let (result, trace) = tree.get_traced(1, 2).unwrap();
+            assert_eq!(result, 10);
+            assert_eq!(trace.nodes_visited(), 5);
+            assert_eq!(trace.combine_calls(), 1);
+            assert_eq!(trace.max_depth(), 3);
+
+            assert_eq!(
+                tree.get_traced(2, 1),
+                Err(SegmentTreeError::InvalidRange { left: 2, right: 1 })
+            );
+        }
+
+        #[test]
+        fn test_statistics() {
+            let mut tree = SumSegmentTree::build(&[1, 3, 7, 27]);
+
+            assert_eq!(tree.get(0, 3), Ok(38));
+            assert_eq!(tree.get(1, 2), Ok(10));
+            assert_eq!(tree.modify(3, &0), Ok(()));
+            assert_eq!(
+                tree.modify(4, &0),
+                Err(SegmentTreeError::OutOfBounds { index: 4, len: 4 })
+            );
+
+            let trace = tree.modify_traced(0, &2).unwrap();
+            assert_eq!(trace.nodes_visited(), 3);
+            assert_eq!(trace.combine_calls(), 2);
+
+            let statistics = tree.statistics();
+            assert_eq!(statistics.build().calls(), 1);
+            assert_eq!(statistics.build().nodes_visited(), 7);
+            assert_eq!(statistics.get().calls(), 2);
+            assert_eq!(statistics.get().nodes_visited(), 6);
+            assert_eq!(statistics.get().combine_calls(), 1);
+            assert_eq!(statistics.get().max_depth(), 3);
+            assert_eq!(statistics.modify().calls(), 2);
+            assert_eq!(statistics.modify().nodes_visited(), 6);
+            assert_eq!(statistics.modify().combine_calls(), 4);
+
+            tree.reset_statistics();
+            assert_eq!(tree.statistics(), Default::default());
+        }
+
+        #[test]
+        fn test_highlighting_is_not_recorded() {
+            let tree = SumSegmentTree::build(&[1, 3, 7, 27]);
+
+            assert!(tree.to_ascii_highlighting(1, 2).is_ok());
+            assert!(tree.to_dot_highlighting(0, 3).is_ok());
+            assert_eq!(tree.statistics().get().calls(), 0);
+        }
+    }
+}
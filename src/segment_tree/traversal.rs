@@ -0,0 +1,106 @@
+use super::trace::Tracer;
+
+/// Nodes of a recursive segment tree over `0..len`. Node `index` covers
+/// `cur_left..=cur_right` and its children are `index * 2` and `index * 2 + 1`.
+pub(crate) trait Nodes {
+    type Output;
+
+    fn node(&self, index: usize, cur_left: usize, cur_right: usize) -> Self::Output;
+
+    fn combine(&self, left: &Self::Output, right: &Self::Output) -> Self::Output;
+}
+
+/// Node values computed bottom-up while a tree is built.
+pub(crate) trait Builder {
+    type Output;
+
+    fn leaf(&mut self, pos: usize) -> Self::Output;
+
+    fn combine(&mut self, left: &Self::Output, right: &Self::Output) -> Self::Output;
+
+    fn store(&mut self, index: usize, cur_left: usize, cur_right: usize, value: &Self::Output);
+}
+
+/// Builds the subtree of `index` and returns its value.
+pub(crate) fn build<B: Builder, T: Tracer>(
+    builder: &mut B,
+    index: usize,
+    cur_left: usize,
+    cur_right: usize,
+    tracer: &mut T,
+) -> B::Output {
+    tracer.enter(index);
+
+    let value = if cur_left == cur_right {
+        builder.leaf(cur_left)
+    } else {
+        let mid = (cur_left + cur_right) / 2;
+        let left_value = build(builder, index * 2, cur_left, mid, tracer);
+        let right_value = build(builder, index * 2 + 1, mid + 1, cur_right, tracer);
+
+        tracer.combine();
+        builder.combine(&left_value, &right_value)
+    };
+    builder.store(index, cur_left, cur_right, &value);
+
+    tracer.exit();
+    value
+}
+
+/// Combines the nodes below `index` that exactly cover `left..=right`.
+pub(crate) fn fold<N: Nodes, T: Tracer>(
+    nodes: &N,
+    index: usize,
+    cur_left: usize,
+    cur_right: usize,
+    left: usize,
+    right: usize,
+    tracer: &mut T,
+) -> N::Output {
+    tracer.enter(index);
+
+    let result = if left == cur_left && right == cur_right {
+        nodes.node(index, cur_left, cur_right)
+    } else {
+        let mid = (cur_left + cur_right) / 2;
+
+        if mid < left {
+            fold(
+                nodes,
+                index * 2 + 1,
+                mid + 1,
+                cur_right,
+                left,
+                right,
+                tracer,
+            )
+        } else if mid + 1 > right {
+            fold(nodes, index * 2, cur_left, mid, left, right, tracer)
+        } else {
+            let left_result = fold(
+                nodes,
+                index * 2,
+                cur_left,
+                mid,
+                left,
+                right.min(mid),
+                tracer,
+            );
+            let right_result = fold(
+                nodes,
+                index * 2 + 1,
+                mid + 1,
+                cur_right,
+                left.max(mid + 1),
+                right,
+                tracer,
+            );
+
+            tracer.combine();
+            nodes.combine(&left_result, &right_result)
+        }
+    };
+
+    tracer.exit();
+    result
+}
@@ -0,0 +1,269 @@
+use super::{trace::Tracer, traversal, Layout, SegmentTree};
+use crate::{computation::SegmentTreeComputation, errors::SegmentTreeResult};
+use alloc::{format, string::String, vec, vec::Vec};
+use core::fmt::{Debug, Write};
+
+const HIGHLIGHT_MARKER: &str = " *";
+
+/// Records the nodes a traversal enters, in visiting order.
+#[derive(Default)]
+struct VisitedNodes(Vec<usize>);
+
+impl Tracer for VisitedNodes {
+    fn enter(&mut self, index: usize) {
+        self.0.push(index);
+    }
+
+    fn exit(&mut self) {}
+
+    fn combine(&mut self) {}
+}
+
+impl<I, O, C, L> SegmentTree<I, O, C, L>
+where
+    O: Default + Clone + Copy + Debug,
+    C: SegmentTreeComputation<Input = I, Output = O>,
+    L: Layout,
+{
+    pub fn to_dot(&self) -> String {
+        self.render_dot(&[])
+    }
+
+    pub fn to_dot_highlighting(&self, left: usize, right: usize) -> SegmentTreeResult<String> {
+        let visited = self.visited_nodes(left, right)?;
+        Ok(self.render_dot(&visited))
+    }
+
+    pub fn to_ascii(&self) -> String {
+        self.render_ascii(&[])
+    }
+
+    /// Marks every node visited by `get(left, right)` with a trailing `*`.
+    pub fn to_ascii_highlighting(&self, left: usize, right: usize) -> SegmentTreeResult<String> {
+        let visited = self.visited_nodes(left, right)?;
+        Ok(self.render_ascii(&visited))
+    }
+
+    fn visited_nodes(&self, left: usize, right: usize) -> SegmentTreeResult<Vec<usize>> {
+        self.check_range(left, right)?;
+
+        let mut visited = VisitedNodes::default();
+        traversal::fold(self, 1, 0, self.len - 1, left, right, &mut visited);
+        Ok(visited.0)
+    }
+
+    fn for_each_node(&self, mut visit: impl FnMut(usize, usize, usize, usize)) {
+        if self.is_empty() {
+            return;
+        }
+
+        let mut stack = vec![(1, 0, self.len - 1, 0)];
+        while let Some((index, cur_left, cur_right, depth)) = stack.pop() {
+            visit(index, cur_left, cur_right, depth);
+
+            if cur_left != cur_right {
+                let mid = (cur_left + cur_right) / 2;
+                stack.push((index * 2 + 1, mid + 1, cur_right, depth + 1));
+                stack.push((index * 2, cur_left, mid, depth + 1));
+            }
+        }
+    }
+
+    fn render_dot(&self, highlighted: &[usize]) -> String {
+        let mut dot = String::from("digraph SegmentTree {\n    node [shape=box];\n");
+
+        self.for_each_node(|index, cur_left, cur_right, _| {
+            let value = format!("{:?}", self.node(index, cur_left, cur_right))
+                .replace('\\', "\\\\")
+                .replace('"', "\\\"");
+            let style = if highlighted.contains(&index) {
+                ", style=filled, fillcolor=lightblue"
+            } else {
+                ""
+            };
+
+            writeln!(
+                dot,
+                "    n{index} [label=\"#{index} [{cur_left}, {cur_right}]\\n{value}\"{style}];"
+            )
+            .expect("Writing to a String never fails");
+            if cur_left != cur_right {
+                writeln!(dot, "    n{index} -> n{};", index * 2)
+                    .expect("Writing to a String never fails");
+                writeln!(dot, "    n{index} -> n{};", index * 2 + 1)
+                    .expect("Writing to a String never fails");
+            }
+        });
+
+        dot.push_str("}\n");
+        dot
+    }
+
+    fn render_ascii(&self, highlighted: &[usize]) -> String {
+        let mut ascii = String::new();
+        // Whether each ancestor level still has a sibling below, to draw `│`.
+        let mut open_levels: Vec<bool> = vec![];
+
+        self.for_each_node(|index, cur_left, cur_right, depth| {
+            open_levels.truncate(depth);
+
+            if depth > 0 {
+                for &open in &open_levels[1..] {
+                    ascii.push_str(if open { "│   " } else { "    " });
+                }
+
+                let is_left_child = index % 2 == 0;
+                ascii.push_str(if is_left_child {
+                    "├── "
+                } else {
+                    "└── "
+                });
+                open_levels.push(is_left_child);
+            } else {
+                open_levels.push(false);
+            }
+
+            let marker = if highlighted.contains(&index) {
+                HIGHLIGHT_MARKER
+            } else {
+                ""
+            };
+            writeln!(
+                ascii,
+                "#{index} [{cur_left}, {cur_right}] = {:?}{marker}",
+                self.node(index, cur_left, cur_right)
+            )
+            .expect("Writing to a String never fails");
+        });
+
+        ascii
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        computation::SegmentTreeComputation, MaxSliceSumSegmentTree, SegmentTree, SegmentTreeError,
+        SumSegmentTree,
+    };
+
+    struct Last;
+
+    impl SegmentTreeComputation for Last {
+        type Input = char;
+        type Output = char;
+
+        fn combine(_: &char, right_result: &char) -> char {
+            *right_result
+        }
+
+        fn update(_: &char, new_value: &char) -> char {
+            *new_value
+        }
+
+        fn init(value: &char) -> char {
+            *value
+        }
+    }
+
+    #[test]
+    fn test_to_ascii() {
+        let tree = SumSegmentTree::build(&[1, 3, 7, 27, 5]);
+        let expected = "\
+#1 [0, 4] = 43
+├── #2 [0, 2] = 11
+│   ├── #4 [0, 1] = 4
+│   │   ├── #8 [0, 0] = 1
+│   │   └── #9 [1, 1] = 3
+│   └── #5 [2, 2] = 7
+└── #3 [3, 4] = 32
+    ├── #6 [3, 3] = 27
+    └── #7 [4, 4] = 5
+";
+
+        assert_eq!(tree.to_ascii(), expected);
+    }
+
+    #[test]
+    fn test_to_ascii_highlighting() {
+        let tree = SumSegmentTree::build(&[1, 3, 7, 27]);
+        let expected = "\
+#1 [0, 3] = 38 *
+├── #2 [0, 1] = 4 *
+│   ├── #4 [0, 0] = 1
+│   └── #5 [1, 1] = 3 *
+└── #3 [2, 3] = 34 *
+    ├── #6 [2, 2] = 7 *
+    └── #7 [3, 3] = 27
+";
+
+        assert_eq!(tree.to_ascii_highlighting(1, 2), Ok(expected.to_string()));
+    }
+
+    #[test]
+    fn test_to_dot() {
+        let tree = SumSegmentTree::build(&[1, 3, 7, 27]);
+        let expected = "\
+digraph SegmentTree {
+    node [shape=box];
+    n1 [label=\"#1 [0, 3]\\n38\"];
+    n1 -> n2;
+    n1 -> n3;
+    n2 [label=\"#2 [0, 1]\\n4\"];
+    n2 -> n4;
+    n2 -> n5;
+    n4 [label=\"#4 [0, 0]\\n1\"];
+    n5 [label=\"#5 [1, 1]\\n3\"];
+    n3 [label=\"#3 [2, 3]\\n34\"];
+    n3 -> n6;
+    n3 -> n7;
+    n6 [label=\"#6 [2, 2]\\n7\"];
+    n7 [label=\"#7 [3, 3]\\n27\"];
+}
+";
+
+        assert_eq!(tree.to_dot(), expected);
+    }
+
+    #[test]
+    fn test_to_dot_highlighting() {
+        let tree = MaxSliceSumSegmentTree::build(&[-1, 2, 3]);
+        let dot = tree.to_dot_highlighting(1, 2).unwrap();
+
+        assert!(dot.contains("n1 [label=\"#1 [0, 2]\\nMaxSliceSum { total_sum: 4, best_sum: 5, best_prefix: 4, best_suffix: 5 }\", style=filled, fillcolor=lightblue];"));
+        assert!(dot.contains("n3 [label=\"#3 [2, 2]\\nMaxSliceSum { total_sum: 3, best_sum: 3, best_prefix: 3, best_suffix: 3 }\", style=filled, fillcolor=lightblue];"));
+        assert!(dot.contains("n4 [label=\"#4 [0, 0]\\nMaxSliceSum { total_sum: -1, best_sum: -1, best_prefix: -1, best_suffix: -1 }\"];"));
+        assert_eq!(dot.matches("style=filled").count(), 4);
+    }
+
+    #[test]
+    fn test_dot_escaping() {
+        let tree = SegmentTree::<char, char, Last>::build(&['"', '\\']);
+        let dot = tree.to_dot();
+
+        assert!(dot.contains(r##"n2 [label="#2 [0, 0]\n'\"'"];"##));
+        assert!(dot.contains(r##"n3 [label="#3 [1, 1]\n'\\\\'"];"##));
+    }
+
+    #[test]
+    fn test_errors_and_empty_tree() {
+        let tree = SumSegmentTree::build(&[1, 3]);
+
+        assert_eq!(
+            tree.to_dot_highlighting(1, 0),
+            Err(SegmentTreeError::InvalidRange { left: 1, right: 0 })
+        );
+        assert_eq!(
+            tree.to_ascii_highlighting(0, 2),
+            Err(SegmentTreeError::OutOfBounds { index: 2, len: 2 })
+        );
+
+        let empty = SumSegmentTree::<i32>::build(&[]);
+
+        assert_eq!(empty.to_ascii(), "");
+        assert_eq!(
+            empty.to_dot(),
+            "digraph SegmentTree {\n    node [shape=box];\n}\n"
+        );
+    }
+}
@@ -0,0 +1,422 @@
+use crate::{
+    computation::{append, MaxComputation, SegmentTreeComputation, SumComputation},
+    errors::{SegmentTreeError, SegmentTreeResult},
+};
+use alloc::boxed::Box;
+use core::marker::PhantomData;
+
+type Link<O> = Option<Box<Node<O>>>;
+
+struct Node<O> {
+    aggregate: O,
+    left: Link<O>,
+    right: Link<O>,
+}
+
+/// Segment tree over positions `0..len` where only assigned positions take
+/// up memory, so `len` can be far larger than the number of elements.
+///
+/// Nodes exist only above assigned positions, and `get` returns `None` for
+/// ranges without any. Because the shape of the tree depends only on `len`,
+/// trees over the same positions can be cut with `split_at` in `O(log len)`
+/// and joined with `merge` in time proportional to the nodes they share,
+/// instead of rebuilding either side.
+pub struct SparseSegmentTree<I, O, C>
+where
+    C: SegmentTreeComputation,
+{
+    root: Link<O>,
+    len: usize,
+    phantom: PhantomData<(C, I)>,
+}
+
+pub type SumSparseSegmentTree<T> = SparseSegmentTree<T, T, SumComputation<T>>;
+pub type MaxSparseSegmentTree<T> = SparseSegmentTree<T, T, MaxComputation<T>>;
+
+impl<I, O, C> SparseSegmentTree<I, O, C>
+where
+    O: Copy,
+    C: SegmentTreeComputation<Input = I, Output = O>,
+{
+    /// Empty tree over positions `0..len`.
+    pub fn new(len: usize) -> Self {
+        Self {
+            root: None,
+            len,
+            phantom: PhantomData,
+        }
+    }
+
+    /// Tree over `0..arr.len()` with every position assigned.
+    pub fn build(arr: &[I]) -> Self {
+        let root = if arr.is_empty() {
+            None
+        } else {
+            Some(Self::internal_build(arr, 0, arr.len() - 1))
+        };
+
+        Self {
+            root,
+            len: arr.len(),
+            phantom: PhantomData,
+        }
+    }
+
+    pub fn get(&self, left: usize, right: usize) -> SegmentTreeResult<Option<O>> {
+        if left > right {
+            Err(SegmentTreeError::InvalidRange { left, right })
+        } else if right >= self.len {
+            Err(SegmentTreeError::OutOfBounds {
+                index: right,
+                len: self.len,
+            })
+        } else {
+            Ok(Self::internal_get(&self.root, 0, self.len - 1, left, right))
+        }
+    }
+
+    /// Updates the element at `pos`, or assigns it with `init` if there was none.
+    pub fn modify(&mut self, pos: usize, value: &I) -> SegmentTreeResult<()> {
+        if pos >= self.len {
+            return Err(SegmentTreeError::OutOfBounds {
+                index: pos,
+                len: self.len,
+            });
+        }
+
+        Self::internal_modify(&mut self.root, 0, self.len - 1, pos, value);
+
+        Ok(())
+    }
+
+    /// Moves every element at a position of at least `mid` into the returned
+    /// tree. Both trees keep spanning `0..len`.
+    pub fn split_at(&mut self, mid: usize) -> SegmentTreeResult<Self> {
+        if mid > self.len {
+            return Err(SegmentTreeError::OutOfBounds {
+                index: mid,
+                len: self.len,
+            });
+        }
+
+        let right = match self.root.take() {
+            Some(root) => {
+                let (left, right) = Self::internal_split(root, 0, self.len - 1, mid);
+                self.root = left;
+                right
+            }
+            None => None,
+        };
+
+        Ok(Self {
+            root: right,
+            len: self.len,
+            phantom: PhantomData,
+        })
+    }
+
+    /// Joins two trees over the same positions. Elements assigned in both are
+    /// combined, with the one from `left` first.
+    pub fn merge(left: Self, right: Self) -> SegmentTreeResult<Self> {
+        if left.len != right.len {
+            return Err(SegmentTreeError::LengthMismatch {
+                left: left.len,
+                right: right.len,
+            });
+        }
+
+        Ok(Self {
+            root: Self::internal_merge(left.root, right.root, 0, left.len.saturating_sub(1)),
+            len: left.len,
+            phantom: PhantomData,
+        })
+    }
+
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    fn leaf(aggregate: O) -> Box<Node<O>> {
+        Box::new(Node {
+            aggregate,
+            left: None,
+            right: None,
+        })
+    }
+
+    fn aggregate(left: &Link<O>, right: &Link<O>) -> Option<O> {
+        match (left, right) {
+            (Some(left), Some(right)) => Some(C::combine(&left.aggregate, &right.aggregate)),
+            (Some(child), None) | (None, Some(child)) => Some(child.aggregate),
+            (None, None) => None,
+        }
+    }
+
+    /// Inner node over the given children, or `None` when both are missing.
+    fn join(left: Link<O>, right: Link<O>) -> Link<O> {
+        Self::aggregate(&left, &right).map(|aggregate| {
+            Box::new(Node {
+                aggregate,
+                left,
+                right,
+            })
+        })
+    }
+
+    fn internal_build(input: &[I], cur_left: usize, cur_right: usize) -> Box<Node<O>> {
+        if cur_left == cur_right {
+            Self::leaf(C::init(&input[cur_left]))
+        } else {
+            let mid = (cur_left + cur_right) / 2;
+            let left = Self::internal_build(input, cur_left, mid);
+            let right = Self::internal_build(input, mid + 1, cur_right);
+
+            Self::join(Some(left), Some(right)).expect("Both children exist")
+        }
+    }
+
+    fn internal_get(
+        link: &Link<O>,
+        cur_left: usize,
+        cur_right: usize,
+        left: usize,
+        right: usize,
+    ) -> Option<O> {
+        let node = link.as_ref()?;
+
+        if left == cur_left && right == cur_right {
+            return Some(node.aggregate);
+        }
+
+        let mid = (cur_left + cur_right) / 2;
+
+        if mid < left {
+            Self::internal_get(&node.right, mid + 1, cur_right, left, right)
+        } else if mid + 1 > right {
+            Self::internal_get(&node.left, cur_left, mid, left, right)
+        } else {
+            let left_result = Self::internal_get(&node.left, cur_left, mid, left, mid);
+            let right_result = Self::internal_get(&node.right, mid + 1, cur_right, mid + 1, right);
+
+            match right_result {
+                Some(right_result) => Some(append::<C>(left_result, right_result)),
+                None => left_result,
+            }
+        }
+    }
+
+    fn internal_modify(
+        link: &mut Link<O>,
+        cur_left: usize,
+        cur_right: usize,
+        pos: usize,
+        value: &I,
+    ) {
+        if cur_left == cur_right {
+            match link {
+                Some(node) => node.aggregate = C::update(&node.aggregate, value),
+                None => *link = Some(Self::leaf(C::init(value))),
+            }
+            return;
+        }
+
+        let mid = (cur_left + cur_right) / 2;
+        let Some(node) = link else {
+            let (mut left, mut right) = (None, None);
+            if pos <= mid {
+                Self::internal_modify(&mut left, cur_left, mid, pos, value);
+            } else {
+                Self::internal_modify(&mut right, mid + 1, cur_right, pos, value);
+            }
+
+            *link = Self::join(left, right);
+            return;
+        };
+
+        if pos <= mid {
+            Self::internal_modify(&mut node.left, cur_left, mid, pos, value);
+        } else {
+            Self::internal_modify(&mut node.right, mid + 1, cur_right, pos, value);
+        }
+
+        node.aggregate =
+            Self::aggregate(&node.left, &node.right).expect("A child was just assigned");
+    }
+
+    fn internal_split(
+        node: Box<Node<O>>,
+        cur_left: usize,
+        cur_right: usize,
+        mid: usize,
+    ) -> (Link<O>, Link<O>) {
+        if mid <= cur_left {
+            return (None, Some(node));
+        } else if mid > cur_right {
+            return (Some(node), None);
+        }
+
+        let half = (cur_left + cur_right) / 2;
+        let Node { left, right, .. } = *node;
+
+        let (left_left, left_right) = match left {
+            Some(left) => Self::internal_split(left, cur_left, half, mid),
+            None => (None, None),
+        };
+        let (right_left, right_right) = match right {
+            Some(right) => Self::internal_split(right, half + 1, cur_right, mid),
+            None => (None, None),
+        };
+
+        (
+            Self::join(left_left, right_left),
+            Self::join(left_right, right_right),
+        )
+    }
+
+    fn internal_merge(left: Link<O>, right: Link<O>, cur_left: usize, cur_right: usize) -> Link<O> {
+        let (mut left, right) = match (left, right) {
+            (Some(left), Some(right)) => (left, right),
+            (link, None) | (None, link) => return link,
+        };
+
+        if cur_left == cur_right {
+            left.aggregate = C::combine(&left.aggregate, &right.aggregate);
+        } else {
+            let mid = (cur_left + cur_right) / 2;
+            let Node {
+                left: right_left,
+                right: right_right,
+                ..
+            } = *right;
+
+            left.left = Self::internal_merge(left.left.take(), right_left, cur_left, mid);
+            left.right = Self::internal_merge(left.right.take(), right_right, mid + 1, cur_right);
+            left.aggregate =
+                Self::aggregate(&left.left, &left.right).expect("Merged children are not empty");
+        }
+
+        Some(left)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{MaxSparseSegmentTree, SparseSegmentTree, SumSparseSegmentTree};
+    use crate::{
+        computation::{RollingHash, RollingHashComputation},
+        testing::{Rng, Source},
+        SegmentTreeError,
+    };
+    use std::collections::BTreeMap;
+
+    type HashTree = SparseSegmentTree<u8, RollingHash, RollingHashComputation<u8>>;
+
+    fn verify(tree: &HashTree, expected: &BTreeMap<usize, u8>) {
+        for left in 0..tree.len() {
+            for right in left..tree.len() {
+                let values: Vec<u8> = expected.range(left..=right).map(|(_, &v)| v).collect();
+                let hash = (!values.is_empty()).then(|| RollingHash::from_slice(&values));
+
+                assert_eq!(tree.get(left, right), Ok(hash));
+            }
+        }
+    }
+
+    #[test]
+    fn test_get_and_modify() {
+        let len = 1 << 40;
+        let mut sum = SumSparseSegmentTree::new(len);
+        let mut max = MaxSparseSegmentTree::new(len);
+
+        for (pos, value) in [(7, 3i64), (1 << 39, -2), (len - 1, 10), (7, 4)] {
+            assert_eq!(sum.modify(pos, &value), Ok(()));
+            assert_eq!(max.modify(pos, &value), Ok(()));
+        }
+
+        assert_eq!(sum.get(0, len - 1), Ok(Some(12)));
+        assert_eq!(sum.get(8, 1 << 39), Ok(Some(-2)));
+        assert_eq!(sum.get(8, (1 << 39) - 1), Ok(None));
+        assert_eq!(max.get(0, 1 << 39), Ok(Some(4)));
+        assert_eq!(max.get(len - 1, len - 1), Ok(Some(10)));
+
+        let dense = SumSparseSegmentTree::build(&[1, 2, 3, 4]);
+        assert_eq!(dense.get(1, 3), Ok(Some(9)));
+    }
+
+    #[test]
+    fn test_split_and_merge() {
+        let mut rng = Rng::new(7);
+        let len = 37;
+
+        for _ in 0..20 {
+            let mut expected = BTreeMap::new();
+            let mut tree = HashTree::new(len);
+            for _ in 0..rng.below(len) {
+                let (pos, value) = (rng.below(len), b'a' + rng.below(26) as u8);
+                expected.insert(pos, value);
+                assert_eq!(tree.modify(pos, &value), Ok(()));
+            }
+
+            let mid = rng.below(len + 1);
+            let right = tree.split_at(mid).unwrap();
+            let expected_right = expected.split_off(&mid);
+            assert_eq!(right.len(), len);
+            verify(&tree, &expected);
+            verify(&right, &expected_right);
+
+            let merged = HashTree::merge(tree, right).unwrap();
+            expected.extend(expected_right);
+            verify(&merged, &expected);
+        }
+    }
+
+    #[test]
+    fn test_merge_overlapping() {
+        let mut left = SumSparseSegmentTree::new(10);
+        let mut right = SumSparseSegmentTree::new(10);
+        left.modify(2, &5).unwrap();
+        left.modify(9, &1).unwrap();
+        right.modify(2, &7).unwrap();
+        right.modify(4, &-1).unwrap();
+
+        let merged = SumSparseSegmentTree::merge(left, right).unwrap();
+        assert_eq!(merged.get(2, 2), Ok(Some(12)));
+        assert_eq!(merged.get(0, 9), Ok(Some(12)));
+        assert_eq!(merged.get(5, 8), Ok(None));
+    }
+
+    #[test]
+    fn test_errors() {
+        let mut tree = SumSparseSegmentTree::<i32>::new(3);
+        let (left, right) = (2, 1);
+
+        assert_eq!(
+            tree.get(left, right),
+            Err(SegmentTreeError::InvalidRange { left, right })
+        );
+        assert_eq!(
+            tree.get(0, 3),
+            Err(SegmentTreeError::OutOfBounds { index: 3, len: 3 })
+        );
+        assert_eq!(
+            tree.modify(3, &1),
+            Err(SegmentTreeError::OutOfBounds { index: 3, len: 3 })
+        );
+        assert_eq!(
+            tree.split_at(4).err(),
+            Some(SegmentTreeError::OutOfBounds { index: 4, len: 3 })
+        );
+        assert_eq!(
+            SumSparseSegmentTree::merge(tree, SumSparseSegmentTree::new(4)).err(),
+            Some(SegmentTreeError::LengthMismatch { left: 3, right: 4 })
+        );
+
+        let mut empty = SumSparseSegmentTree::<i32>::new(0);
+        assert!(empty.is_empty());
+        assert_eq!(empty.split_at(0).map(|tree| tree.len()), Ok(0));
+    }
+}
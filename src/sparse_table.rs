@@ -0,0 +1,211 @@
+use crate::{
+    computation::{
+        BitAndComputation, BitOrComputation, GcdComputation, IdempotentComputation, MaxComputation,
+        MinComputation,
+    },
+    errors::{SegmentTreeError, SegmentTreeResult},
+};
+use alloc::{vec, vec::Vec};
+use core::marker::PhantomData;
+
+/// Static structure answering range queries in O(1) after an O(n log n) build.
+///
+/// Only idempotent computations are accepted, so sums are rejected:
+///
+/// ```compile_fail
+/// use segment_tree::{SparseTable, SumComputation};
+///
+/// let table = SparseTable::<i32, i32, SumComputation<i32>>::build(&[1, 2, 3]);
+/// ```
+pub struct SparseTable<I, O, C>
+where
+    C: IdempotentComputation,
+{
+    levels: Vec<Vec<O>>,
+    len: usize,
+    phantom: PhantomData<(C, I)>,
+}
+
+pub type MaxSparseTable<T> = SparseTable<T, T, MaxComputation<T>>;
+pub type MinSparseTable<T> = SparseTable<T, T, MinComputation<T>>;
+pub type GcdSparseTable<T> = SparseTable<T, T, GcdComputation<T>>;
+pub type BitAndSparseTable<T> = SparseTable<T, T, BitAndComputation<T>>;
+pub type BitOrSparseTable<T> = SparseTable<T, T, BitOrComputation<T>>;
+
+impl<I, O, C> SparseTable<I, O, C>
+where
+    O: Clone,
+    C: IdempotentComputation<Input = I, Output = O>,
+{
+    pub fn build(arr: &[I]) -> Self {
+        let len = arr.len();
+        let mut levels: Vec<Vec<O>> = vec![];
+
+        if len > 0 {
+            levels.push(arr.iter().map(C::init).collect());
+        }
+
+        let mut width = 1;
+        while width * 2 <= len {
+            let prev = levels.last().expect("Previous level exists");
+            let level = (0..=len - width * 2)
+                .map(|index| C::combine(&prev[index], &prev[index + width]))
+                .collect();
+
+            levels.push(level);
+            width *= 2;
+        }
+
+        Self {
+            levels,
+            len,
+            phantom: PhantomData,
+        }
+    }
+
+    pub fn get(&self, left: usize, right: usize) -> SegmentTreeResult<O> {
+        if left > right {
+            Err(SegmentTreeError::InvalidRange { left, right })
+        } else if right >= self.len {
+            Err(SegmentTreeError::OutOfBounds {
+                index: right,
+                len: self.len,
+            })
+        } else {
+            let level = (right - left + 1).ilog2() as usize;
+            let values = &self.levels[level];
+
+            Ok(C::combine(&values[left], &values[right + 1 - (1 << level)]))
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{
+        BitAndSparseTable, BitOrSparseTable, GcdSparseTable, MaxSparseTable, MinSparseTable,
+    };
+    use crate::{GcdComputation, SegmentTree, SegmentTreeError, SegmentTreeResult};
+
+    #[test]
+    fn test_build() {
+        let arr = [1, 3, 7, 27, 5];
+        let table = MaxSparseTable::build(&arr);
+
+        assert!(!table.is_empty());
+        assert_eq!(table.len(), arr.len());
+        assert_eq!(
+            table.levels,
+            vec![vec![1, 3, 7, 27, 5], vec![3, 7, 27, 27], vec![27, 27]]
+        );
+    }
+
+    #[test]
+    fn test_empty_table() {
+        let arr: [usize; 0] = [];
+        let table = MinSparseTable::build(&arr);
+
+        assert!(table.is_empty());
+        assert_eq!(table.len(), 0);
+
+        for value in [0, 1, 2, 100] {
+            let expected = Err(SegmentTreeError::OutOfBounds {
+                index: value,
+                len: 0,
+            });
+
+            assert_eq!(table.get(value, value), expected);
+        }
+    }
+
+    #[test]
+    fn test_get() {
+        let arr = [1, 3, 7, 27, 73, 7542, 1, -5, -543, 9, 0];
+        let max_table = MaxSparseTable::build(&arr);
+        let min_table = MinSparseTable::build(&arr);
+
+        for left in 0..arr.len() {
+            for right in left..arr.len() {
+                let expected: SegmentTreeResult<i32> = Ok(*arr[left..=right].iter().max().unwrap());
+                assert_eq!(max_table.get(left, right), expected);
+
+                let expected: SegmentTreeResult<i32> = Ok(*arr[left..=right].iter().min().unwrap());
+                assert_eq!(min_table.get(left, right), expected);
+            }
+        }
+    }
+
+    #[test]
+    fn test_get_errors() {
+        let arr = [1, 3, 7, 27, 73];
+        let table = MaxSparseTable::build(&arr);
+
+        for (left, right) in [(0, 5), (1, 6), (6, 1234), (3, 435345)] {
+            let expected = Err(SegmentTreeError::OutOfBounds {
+                index: right,
+                len: 5,
+            });
+
+            assert_eq!(table.get(left, right), expected);
+        }
+
+        for (left, right) in [(5, 4), (2, 1), (2, 0), (4, 1)] {
+            let expected = Err(SegmentTreeError::InvalidRange { left, right });
+
+            assert_eq!(table.get(left, right), expected);
+        }
+    }
+
+    #[test]
+    fn test_gcd_and_bitwise() {
+        let arr = [12u32, 18, 24, 36, 7, 14, 28, 0b1111];
+        let gcd_table = GcdSparseTable::build(&arr);
+        let and_table = BitAndSparseTable::build(&arr);
+        let or_table = BitOrSparseTable::build(&arr);
+
+        for left in 0..arr.len() {
+            for right in left..arr.len() {
+                let slice = &arr[left..=right];
+                let gcd = slice.iter().fold(0, |mut a, &b| {
+                    let mut b = b;
+                    while b != 0 {
+                        (a, b) = (b, a % b);
+                    }
+                    a
+                });
+
+                assert_eq!(gcd_table.get(left, right), Ok(gcd));
+                assert_eq!(
+                    and_table.get(left, right),
+                    Ok(slice.iter().fold(u32::MAX, |acc, cur| acc & cur))
+                );
+                assert_eq!(
+                    or_table.get(left, right),
+                    Ok(slice.iter().fold(0, |acc, cur| acc | cur))
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn test_gcd_negative() {
+        let arr = [-12i64, 18, -7, -24, 0, -36];
+        let table = GcdSparseTable::build(&arr);
+        let tree = SegmentTree::<i64, i64, GcdComputation<i64>>::build(&arr);
+
+        for left in 0..arr.len() {
+            for right in left..arr.len() {
+                assert_eq!(table.get(left, right), tree.get(left, right));
+            }
+        }
+        assert_eq!(table.get(2, 2), Ok(7));
+    }
+}
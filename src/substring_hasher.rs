@@ -0,0 +1,178 @@
+use alloc::vec::Vec;
+use core::ops::RangeInclusive;
+
+use crate::{
+    computation::RollingHash, errors::SegmentTreeResult, segment_tree::RollingHashSegmentTree,
+};
+
+pub struct SubstringHasher<T>
+where
+    T: Copy + Into<u64>,
+{
+    forward: RollingHashSegmentTree<T>,
+    backward: RollingHashSegmentTree<T>,
+}
+
+impl<T> SubstringHasher<T>
+where
+    T: Copy + Into<u64>,
+{
+    pub fn build(arr: &[T]) -> Self {
+        let reversed: Vec<T> = arr.iter().rev().cloned().collect();
+
+        Self {
+            forward: RollingHashSegmentTree::build(arr),
+            backward: RollingHashSegmentTree::build(&reversed),
+        }
+    }
+
+    pub fn hash(&self, range: RangeInclusive<usize>) -> SegmentTreeResult<RollingHash> {
+        self.forward.get(*range.start(), *range.end())
+    }
+
+    pub fn substring_eq(
+        &self,
+        a_range: RangeInclusive<usize>,
+        b_range: RangeInclusive<usize>,
+    ) -> SegmentTreeResult<bool> {
+        let a_hash = self.hash(a_range)?;
+        let b_hash = self.hash(b_range)?;
+
+        Ok(a_hash == b_hash)
+    }
+
+    pub fn is_palindrome(&self, range: RangeInclusive<usize>) -> SegmentTreeResult<bool> {
+        let (left, right) = (*range.start(), *range.end());
+        let forward_hash = self.forward.get(left, right)?;
+        let backward_hash = self
+            .backward
+            .get(self.len() - 1 - right, self.len() - 1 - left)?;
+
+        Ok(forward_hash == backward_hash)
+    }
+
+    pub fn modify(&mut self, pos: usize, value: &T) -> SegmentTreeResult<()> {
+        self.forward.modify(pos, value)?;
+        self.backward.modify(self.len() - 1 - pos, value)
+    }
+
+    pub fn len(&self) -> usize {
+        self.forward.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.forward.is_empty()
+    }
+}
+
+impl From<&str> for SubstringHasher<char> {
+    fn from(value: &str) -> Self {
+        let chars: Vec<char> = value.chars().collect();
+        Self::build(&chars)
+    }
+}
+
+impl From<&[u8]> for SubstringHasher<u8> {
+    fn from(value: &[u8]) -> Self {
+        Self::build(value)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::SubstringHasher;
+    use crate::SegmentTreeError;
+
+    #[test]
+    fn test_substring_eq() {
+        let text = "abracadabra";
+        let bytes = text.as_bytes();
+        let hasher = SubstringHasher::from(bytes);
+
+        assert_eq!(hasher.len(), bytes.len());
+
+        for a_left in 0..bytes.len() {
+            for b_left in 0..bytes.len() {
+                for len in 1..=bytes.len() - a_left.max(b_left) {
+                    let expected = bytes[a_left..a_left + len] == bytes[b_left..b_left + len];
+                    let actual =
+                        hasher.substring_eq(a_left..=a_left + len - 1, b_left..=b_left + len - 1);
+
+                    assert_eq!(actual, Ok(expected));
+                }
+            }
+        }
+
+        assert_eq!(hasher.substring_eq(0..=3, 7..=9), Ok(false));
+    }
+
+    #[test]
+    fn test_is_palindrome() {
+        let chars: Vec<char> = "abacabaї€їx".chars().collect();
+        let hasher = SubstringHasher::build(&chars);
+
+        for left in 0..chars.len() {
+            for right in left..chars.len() {
+                let slice = &chars[left..=right];
+                let expected = slice.iter().eq(slice.iter().rev());
+                let actual = hasher.is_palindrome(left..=right);
+
+                assert_eq!(actual, Ok(expected));
+            }
+        }
+    }
+
+    #[test]
+    fn test_modify() {
+        let mut hasher = SubstringHasher::from("abcdcbx");
+
+        assert_eq!(hasher.is_palindrome(0..=6), Ok(false));
+        assert_eq!(hasher.substring_eq(0..=1, 4..=5), Ok(false));
+
+        assert_eq!(hasher.modify(6, &'a'), Ok(()));
+
+        assert_eq!(hasher.is_palindrome(0..=6), Ok(true));
+        assert_eq!(hasher.substring_eq(0..=0, 6..=6), Ok(true));
+
+        assert_eq!(hasher.modify(4, &'a'), Ok(()));
+        assert_eq!(hasher.modify(5, &'b'), Ok(()));
+
+        assert_eq!(hasher.substring_eq(0..=1, 4..=5), Ok(true));
+        assert_eq!(hasher.is_palindrome(0..=6), Ok(false));
+    }
+
+    #[test]
+    fn test_errors() {
+        let mut hasher = SubstringHasher::from("abc");
+
+        assert_eq!(
+            hasher.substring_eq(0..=1, 2..=3),
+            Err(SegmentTreeError::OutOfBounds { index: 3, len: 3 })
+        );
+        let (left, right) = (2, 1);
+        assert_eq!(
+            hasher.is_palindrome(left..=right),
+            Err(SegmentTreeError::InvalidRange { left, right })
+        );
+        assert_eq!(
+            hasher.is_palindrome(1..=5),
+            Err(SegmentTreeError::OutOfBounds { index: 5, len: 3 })
+        );
+        assert_eq!(
+            hasher.modify(3, &'x'),
+            Err(SegmentTreeError::OutOfBounds { index: 3, len: 3 })
+        );
+
+        let mut empty = SubstringHasher::from("");
+
+        assert!(empty.is_empty());
+        assert_eq!(
+            empty.is_palindrome(0..=0),
+            Err(SegmentTreeError::OutOfBounds { index: 0, len: 0 })
+        );
+        assert_eq!(
+            empty.modify(0, &'x'),
+            Err(SegmentTreeError::OutOfBounds { index: 0, len: 0 })
+        );
+    }
+}
@@ -0,0 +1,395 @@
+use crate::{
+    computation::SegmentTreeComputation,
+    range_query::{NaiveRangeQuery, PointUpdate, RangeQuery},
+    segment_tree::SegmentTree,
+};
+use std::fmt::{self, Debug, Display};
+
+pub mod laws;
+
+/// Source of randomness for generated cases.
+pub trait Source {
+    fn next_u64(&mut self) -> u64;
+
+    /// Uniform-ish value in `0..bound`, `bound` must be positive.
+    fn below(&mut self, bound: usize) -> usize {
+        (self.next_u64() % bound as u64) as usize
+    }
+
+    fn range_i64(&mut self, low: i64, high: i64) -> i64 {
+        low + (self.next_u64() % (high - low + 1) as u64) as i64
+    }
+}
+
+/// Small xorshift generator, so runs are reproducible from a single seed.
+pub struct Rng {
+    state: u64,
+}
+
+impl Rng {
+    pub fn new(seed: u64) -> Self {
+        Self {
+            state: (seed ^ 0x9e37_79b9_7f4a_7c15) | 1,
+        }
+    }
+}
+
+impl Source for Rng {
+    fn next_u64(&mut self) -> u64 {
+        self.state ^= self.state << 13;
+        self.state ^= self.state >> 7;
+        self.state ^= self.state << 17;
+        self.state
+    }
+}
+
+/// Reads values from raw bytes, e.g. fuzzer input, yielding zeros once the
+/// bytes run out.
+pub struct ByteSource<'a> {
+    bytes: &'a [u8],
+}
+
+impl<'a> ByteSource<'a> {
+    pub fn new(bytes: &'a [u8]) -> Self {
+        Self { bytes }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.bytes.is_empty()
+    }
+}
+
+impl Source for ByteSource<'_> {
+    fn next_u64(&mut self) -> u64 {
+        let (head, tail) = self.bytes.split_at(self.bytes.len().min(8));
+        self.bytes = tail;
+
+        let mut buffer = [0; 8];
+        buffer[..head.len()].copy_from_slice(head);
+        u64::from_le_bytes(buffer)
+    }
+}
+
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub enum Operation<I> {
+    Get { left: usize, right: usize },
+    Modify { pos: usize, value: I },
+}
+
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub struct Case<I> {
+    pub values: Vec<I>,
+    pub operations: Vec<Operation<I>>,
+}
+
+#[derive(Debug, Clone)]
+pub struct Failure<I> {
+    pub seed: u64,
+    pub case: Case<I>,
+    pub step: usize,
+    pub message: String,
+}
+
+impl<I: Debug> Display for Failure<I> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "seed {}: {}", self.seed, self.message)?;
+        writeln!(f, "values: {:?}", self.case.values)?;
+        for (step, operation) in self.case.operations.iter().enumerate() {
+            let marker = if step == self.step { " <-" } else { "" };
+            writeln!(f, "{step}: {operation:?}{marker}")?;
+        }
+
+        Ok(())
+    }
+}
+
+#[derive(Debug, Clone, Copy)]
+pub struct Config {
+    pub cases: usize,
+    pub max_len: usize,
+    pub max_operations: usize,
+    pub seed: u64,
+}
+
+impl Default for Config {
+    fn default() -> Self {
+        Self {
+            cases: 256,
+            max_len: 32,
+            max_operations: 64,
+            seed: 0x5eed,
+        }
+    }
+}
+
+/// Runs random cases against `SegmentTree` with the naive oracle and returns
+/// the shrunk first failure. With `debug-invariants` the trees are also
+/// validated after every modification.
+pub fn check<C, G>(config: &Config, generate: G) -> Result<(), Failure<C::Input>>
+where
+    C: SegmentTreeComputation,
+    C::Input: Clone + Debug,
+    C::Output: Default + Copy + PartialEq + Debug,
+    G: FnMut(&mut Rng) -> C::Input,
+{
+    check_with::<C, _, _, _>(
+        config,
+        SegmentTree::<C::Input, C::Output, C>::build,
+        generate,
+    )
+}
+
+/// Same as `check`, but for any structure built by `build`.
+pub fn check_with<C, S, B, G>(
+    config: &Config,
+    build: B,
+    mut generate: G,
+) -> Result<(), Failure<C::Input>>
+where
+    C: SegmentTreeComputation,
+    C::Input: Clone + Debug,
+    C::Output: Clone + PartialEq + Debug,
+    S: PointUpdate<Input = C::Input, Output = C::Output>,
+    B: Fn(&[C::Input]) -> S,
+    G: FnMut(&mut Rng) -> C::Input,
+{
+    for index in 0..config.cases {
+        let seed = config.seed.wrapping_add(index as u64);
+        let mut rng = Rng::new(seed);
+        let case = generate_case(&mut rng, config, &mut generate);
+
+        if run_case::<C, S, B>(&case, &build).is_err() {
+            let case = shrink::<C, S, B>(case, &build);
+            let (step, message) =
+                run_case::<C, S, B>(&case, &build).expect_err("Shrinking keeps the case failing");
+
+            return Err(Failure {
+                seed,
+                case,
+                step,
+                message,
+            });
+        }
+    }
+
+    Ok(())
+}
+
+pub fn generate_case<S, I, G>(rng: &mut S, config: &Config, generate: &mut G) -> Case<I>
+where
+    S: Source,
+    G: FnMut(&mut S) -> I,
+{
+    let len = rng.below(config.max_len + 1);
+    let values = (0..len).map(|_| generate(rng)).collect();
+
+    // Indices may step one past the end so that error paths are compared too.
+    let operations = (0..rng.below(config.max_operations + 1))
+        .map(|_| {
+            if rng.below(2) == 0 {
+                let left = rng.below(len + 1);
+                let right = left + rng.below(len + 1 - left);
+                Operation::Get { left, right }
+            } else {
+                Operation::Modify {
+                    pos: rng.below(len + 1),
+                    value: generate(rng),
+                }
+            }
+        })
+        .collect();
+
+    Case { values, operations }
+}
+
+/// Replays `case` on the subject and on the oracle, returning the first
+/// diverging step and a description of the mismatch.
+pub fn run_case<C, S, B>(case: &Case<C::Input>, build: &B) -> Result<(), (usize, String)>
+where
+    C: SegmentTreeComputation,
+    C::Input: Debug,
+    C::Output: Clone + PartialEq + Debug,
+    S: PointUpdate<Input = C::Input, Output = C::Output>,
+    B: Fn(&[C::Input]) -> S,
+{
+    let mut subject = build(&case.values);
+    let mut oracle = NaiveRangeQuery::<C::Input, C::Output, C>::build(&case.values);
+
+    for (step, operation) in case.operations.iter().enumerate() {
+        match operation {
+            Operation::Get { left, right } => {
+                let expected = oracle.get(*left, *right);
+                let actual = subject.get(*left, *right);
+
+                if actual != expected {
+                    return Err((
+                        step,
+                        format!("get({left}, {right}) returned {actual:?}, expected {expected:?}"),
+                    ));
+                }
+            }
+            Operation::Modify { pos, value } => {
+                let expected = oracle.modify(*pos, value);
+                let actual = subject.modify(*pos, value);
+
+                if actual != expected {
+                    return Err((
+                        step,
+                        format!(
+                            "modify({pos}, {value:?}) returned {actual:?}, expected {expected:?}"
+                        ),
+                    ));
+                }
+            }
+        }
+    }
+
+    Ok(())
+}
+
+/// Greedily drops operations and elements while the case keeps failing.
+pub fn shrink<C, S, B>(mut case: Case<C::Input>, build: &B) -> Case<C::Input>
+where
+    C: SegmentTreeComputation,
+    C::Input: Clone + Debug,
+    C::Output: Clone + PartialEq + Debug,
+    S: PointUpdate<Input = C::Input, Output = C::Output>,
+    B: Fn(&[C::Input]) -> S,
+{
+    let fails = |case: &Case<C::Input>| run_case::<C, S, B>(case, build).is_err();
+
+    if let Err((step, _)) = run_case::<C, S, B>(&case, build) {
+        case.operations.truncate(step + 1);
+    }
+
+    loop {
+        let mut progress = false;
+
+        for index in (0..case.operations.len()).rev() {
+            let mut candidate = case.clone();
+            candidate.operations.remove(index);
+
+            if fails(&candidate) {
+                case = candidate;
+                progress = true;
+            }
+        }
+
+        for index in (0..case.values.len()).rev() {
+            let mut candidate = case.clone();
+            candidate.values.remove(index);
+            candidate.operations.iter_mut().for_each(|operation| {
+                shift_operation(operation, index);
+            });
+
+            if fails(&candidate) {
+                case = candidate;
+                progress = true;
+            }
+        }
+
+        if !progress {
+            return case;
+        }
+    }
+}
+
+fn shift_operation<I>(operation: &mut Operation<I>, removed: usize) {
+    match operation {
+        Operation::Get { left, right } => {
+            if *left > removed {
+                *left -= 1;
+            }
+            if *right > removed {
+                *right -= 1;
+            }
+        }
+        Operation::Modify { pos, .. } => {
+            if *pos > removed {
+                *pos -= 1;
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{check, check_with, generate_case, ByteSource, Config, Operation, Source};
+    use crate::{
+        computation::{MaxSliceSumComputation, SegmentTreeComputation, SumComputation},
+        MaxComputation, SumFenwickTree,
+    };
+
+    struct SubtractComputation;
+
+    impl SegmentTreeComputation for SubtractComputation {
+        type Input = i64;
+
+        type Output = i64;
+
+        fn combine(left_result: &i64, right_result: &i64) -> i64 {
+            left_result - right_result
+        }
+
+        fn update(_: &i64, new_value: &i64) -> i64 {
+            *new_value
+        }
+
+        fn init(value: &i64) -> i64 {
+            *value
+        }
+    }
+
+    #[test]
+    fn test_check_passes() {
+        let config = Config::default();
+
+        assert!(check::<SumComputation<i64>, _>(&config, |rng| rng.range_i64(-100, 100)).is_ok());
+        assert!(check::<MaxComputation<i64>, _>(&config, |rng| rng.range_i64(-100, 100)).is_ok());
+        assert!(
+            check::<MaxSliceSumComputation<i64>, _>(&config, |rng| rng.range_i64(-100, 100))
+                .is_ok()
+        );
+        assert!(check_with::<SumComputation<i64>, _, _, _>(
+            &config,
+            SumFenwickTree::build,
+            |rng| rng.range_i64(-100, 100)
+        )
+        .is_ok());
+    }
+
+    #[test]
+    fn test_check_shrinks_failure() {
+        let config = Config::default();
+        let failure = check::<SubtractComputation, _>(&config, |rng| rng.range_i64(1, 100))
+            .expect_err("Subtraction is not associative");
+
+        // A tree over three elements folds left to right just like the oracle,
+        // so four elements and a single query is the smallest witness.
+        assert_eq!(failure.case.values.len(), 4);
+        assert_eq!(failure.case.operations.len(), 1);
+        assert_eq!(failure.step, 0);
+        assert!(matches!(
+            failure.case.operations[0],
+            Operation::Get { right: 3, .. }
+        ));
+        assert!(failure.to_string().contains("<-"));
+    }
+
+    #[test]
+    fn test_byte_source() {
+        let mut source = ByteSource::new(&[3, 0, 0, 0, 0, 0, 0, 0, 9]);
+
+        assert_eq!(source.below(10), 3);
+        assert_eq!(source.next_u64(), 9);
+        assert!(source.is_empty());
+        assert_eq!(source.next_u64(), 0);
+
+        let config = Config::default();
+        let case = generate_case(&mut ByteSource::new(&[]), &config, &mut |source| {
+            source.range_i64(-5, 5)
+        });
+        assert!(case.values.is_empty());
+        assert!(case.operations.is_empty());
+    }
+}
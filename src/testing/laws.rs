@@ -0,0 +1,443 @@
+use crate::computation::SegmentTreeComputation;
+use std::fmt::{self, Debug, Display};
+
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub enum Law {
+    Associativity,
+    UpdateConsistency,
+    Identity,
+    Commutativity,
+    Idempotence,
+}
+
+impl Display for Law {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let name = match self {
+            Law::Associativity => "associativity",
+            Law::UpdateConsistency => "update consistency",
+            Law::Identity => "identity",
+            Law::Commutativity => "commutativity",
+            Law::Idempotence => "idempotence",
+        };
+
+        f.write_str(name)
+    }
+}
+
+/// Operands for which the two sides of `law` differ. `input` is the value
+/// passed to `update` when update consistency fails.
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub struct Counterexample<I, O> {
+    pub law: Law,
+    pub operands: Vec<O>,
+    pub input: Option<I>,
+    pub left: O,
+    pub right: O,
+}
+
+impl<I: Debug, O: Debug> Display for Counterexample<I, O> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} does not hold for {:?}", self.law, self.operands)?;
+        if let Some(input) = &self.input {
+            write!(f, " updated with {input:?}")?;
+        }
+        write!(f, ": {:?} != {:?}", self.left, self.right)
+    }
+}
+
+/// Associativity is checked on every triple of outputs, so only the first
+/// `MAX_ASSOCIATIVITY_OPERANDS` are used to keep it under a million combines.
+pub const MAX_ASSOCIATIVITY_OPERANDS: usize = 64;
+
+/// Optional laws, associativity is always checked. `replaces` asks for update
+/// consistency, which only holds when `update` overwrites the element: trees
+/// like the sparse tree and the treap accumulate through it instead.
+#[derive(Debug, Clone)]
+pub struct Laws<O> {
+    pub identity: Option<O>,
+    pub commutative: bool,
+    pub idempotent: bool,
+    pub replaces: bool,
+}
+
+impl<O> Default for Laws<O> {
+    fn default() -> Self {
+        Self {
+            identity: None,
+            commutative: false,
+            idempotent: false,
+            replaces: false,
+        }
+    }
+}
+
+/// Checks `laws` on the outputs of `samples` and their pairs, see `outputs`.
+/// Associativity only uses the first `MAX_ASSOCIATIVITY_OPERANDS` of them,
+/// leaves first, while the other laws take time quadratic in their number.
+pub fn check_laws<C>(
+    samples: &[C::Input],
+    laws: &Laws<C::Output>,
+) -> Result<(), Counterexample<C::Input, C::Output>>
+where
+    C: SegmentTreeComputation,
+    C::Input: Clone,
+    C::Output: Clone + PartialEq,
+{
+    let outputs = outputs::<C>(samples);
+
+    check_associativity::<C>(&outputs[..outputs.len().min(MAX_ASSOCIATIVITY_OPERANDS)])?;
+    if laws.replaces {
+        check_update_consistency::<C>(samples, &outputs)?;
+    }
+    if let Some(identity) = &laws.identity {
+        check_identity::<C>(&outputs, identity)?;
+    }
+    if laws.commutative {
+        check_commutativity::<C>(&outputs)?;
+    }
+    if laws.idempotent {
+        check_idempotence::<C>(&outputs)?;
+    }
+
+    Ok(())
+}
+
+/// Leaf outputs of `samples` together with the combination of every ordered
+/// pair, so laws are also exercised on values that only appear in inner nodes.
+pub fn outputs<C>(samples: &[C::Input]) -> Vec<C::Output>
+where
+    C: SegmentTreeComputation,
+{
+    let leaves: Vec<C::Output> = samples.iter().map(C::init).collect();
+    let pairs: Vec<C::Output> = leaves
+        .iter()
+        .flat_map(|a| leaves.iter().map(|b| C::combine(a, b)))
+        .collect();
+
+    leaves.into_iter().chain(pairs).collect()
+}
+
+/// Runs `3 * outputs.len()^3` combines.
+pub fn check_associativity<C>(
+    outputs: &[C::Output],
+) -> Result<(), Counterexample<C::Input, C::Output>>
+where
+    C: SegmentTreeComputation,
+    C::Output: Clone + PartialEq,
+{
+    for a in outputs {
+        for b in outputs {
+            let ab = C::combine(a, b);
+
+            for c in outputs {
+                let left = C::combine(&ab, c);
+                let right = C::combine(a, &C::combine(b, c));
+
+                if left != right {
+                    return Err(Counterexample {
+                        law: Law::Associativity,
+                        operands: vec![a.clone(), b.clone(), c.clone()],
+                        input: None,
+                        left,
+                        right,
+                    });
+                }
+            }
+        }
+    }
+
+    Ok(())
+}
+
+/// For computations whose point updates replace the element, `update(prev,
+/// value)` has to match `init(value)` whatever the previous output was.
+pub fn check_update_consistency<C>(
+    samples: &[C::Input],
+    outputs: &[C::Output],
+) -> Result<(), Counterexample<C::Input, C::Output>>
+where
+    C: SegmentTreeComputation,
+    C::Input: Clone,
+    C::Output: Clone + PartialEq,
+{
+    for prev in outputs {
+        for value in samples {
+            let left = C::update(prev, value);
+            let right = C::init(value);
+
+            if left != right {
+                return Err(Counterexample {
+                    law: Law::UpdateConsistency,
+                    operands: vec![prev.clone()],
+                    input: Some(value.clone()),
+                    left,
+                    right,
+                });
+            }
+        }
+    }
+
+    Ok(())
+}
+
+pub fn check_identity<C>(
+    outputs: &[C::Output],
+    identity: &C::Output,
+) -> Result<(), Counterexample<C::Input, C::Output>>
+where
+    C: SegmentTreeComputation,
+    C::Output: Clone + PartialEq,
+{
+    for a in outputs {
+        for (left, right) in [
+            (C::combine(identity, a), a.clone()),
+            (C::combine(a, identity), a.clone()),
+        ] {
+            if left != right {
+                return Err(Counterexample {
+                    law: Law::Identity,
+                    operands: vec![identity.clone(), a.clone()],
+                    input: None,
+                    left,
+                    right,
+                });
+            }
+        }
+    }
+
+    Ok(())
+}
+
+pub fn check_commutativity<C>(
+    outputs: &[C::Output],
+) -> Result<(), Counterexample<C::Input, C::Output>>
+where
+    C: SegmentTreeComputation,
+    C::Output: Clone + PartialEq,
+{
+    for a in outputs {
+        for b in outputs {
+            let left = C::combine(a, b);
+            let right = C::combine(b, a);
+
+            if left != right {
+                return Err(Counterexample {
+                    law: Law::Commutativity,
+                    operands: vec![a.clone(), b.clone()],
+                    input: None,
+                    left,
+                    right,
+                });
+            }
+        }
+    }
+
+    Ok(())
+}
+
+pub fn check_idempotence<C>(
+    outputs: &[C::Output],
+) -> Result<(), Counterexample<C::Input, C::Output>>
+where
+    C: SegmentTreeComputation,
+    C::Output: Clone + PartialEq,
+{
+    for a in outputs {
+        let left = C::combine(a, a);
+
+        if left != *a {
+            return Err(Counterexample {
+                law: Law::Idempotence,
+                operands: vec![a.clone()],
+                input: None,
+                left,
+                right: a.clone(),
+            });
+        }
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{check_laws, check_update_consistency, Counterexample, Law, Laws};
+    use crate::computation::{
+        GcdComputation, InvertibleComputation, MaxComputation, RollingHashComputation,
+        SegmentTreeComputation, SumComputation, XorComputation,
+    };
+
+    struct AverageComputation;
+
+    impl SegmentTreeComputation for AverageComputation {
+        type Input = i64;
+
+        type Output = i64;
+
+        fn combine(left_result: &i64, right_result: &i64) -> i64 {
+            (left_result + right_result) / 2
+        }
+
+        fn update(prev_value: &i64, new_value: &i64) -> i64 {
+            Self::combine(prev_value, new_value)
+        }
+
+        fn init(value: &i64) -> i64 {
+            *value
+        }
+    }
+
+    struct AccumulatingSum;
+
+    impl SegmentTreeComputation for AccumulatingSum {
+        type Input = i64;
+
+        type Output = i64;
+
+        fn combine(left_result: &i64, right_result: &i64) -> i64 {
+            left_result + right_result
+        }
+
+        fn update(prev_value: &i64, new_value: &i64) -> i64 {
+            prev_value + new_value
+        }
+
+        fn init(value: &i64) -> i64 {
+            *value
+        }
+    }
+
+    #[test]
+    fn test_lawful_computations() {
+        let samples = [-7i64, 0, 3, 12, -1];
+
+        let laws = Laws {
+            identity: Some(SumComputation::<i64>::identity()),
+            commutative: true,
+            idempotent: false,
+            replaces: true,
+        };
+        assert_eq!(check_laws::<SumComputation<i64>>(&samples, &laws), Ok(()));
+
+        let laws = Laws {
+            identity: Some(i64::MIN),
+            commutative: true,
+            idempotent: true,
+            replaces: true,
+        };
+        assert_eq!(check_laws::<MaxComputation<i64>>(&samples, &laws), Ok(()));
+
+        let laws = Laws {
+            identity: Some(0),
+            commutative: true,
+            idempotent: true,
+            replaces: true,
+        };
+        assert_eq!(
+            check_laws::<GcdComputation<u64>>(&[0, 4, 6, 9, 35], &laws),
+            Ok(())
+        );
+
+        let laws = Laws {
+            identity: Some(XorComputation::<u32>::identity()),
+            commutative: true,
+            idempotent: false,
+            replaces: true,
+        };
+        assert_eq!(
+            check_laws::<XorComputation<u32>>(&[0, 5, 9, 255], &laws),
+            Ok(())
+        );
+
+        assert_eq!(
+            check_laws::<RollingHashComputation<u8>>(b"abc", &Laws::default()),
+            Ok(())
+        );
+    }
+
+    #[test]
+    fn test_counterexamples() {
+        let samples = [1i64, 4, 9];
+        let error = check_laws::<AverageComputation>(&samples, &Laws::default()).unwrap_err();
+
+        assert_eq!(
+            error,
+            Counterexample {
+                law: Law::Associativity,
+                operands: vec![1, 1, 4],
+                input: None,
+                left: 2,
+                right: 1,
+            }
+        );
+        assert_eq!(
+            error.to_string(),
+            "associativity does not hold for [1, 1, 4]: 2 != 1"
+        );
+
+        let laws = Laws {
+            commutative: true,
+            ..Laws::default()
+        };
+        let error = check_laws::<RollingHashComputation<u8>>(b"ab", &laws).unwrap_err();
+        assert_eq!(error.law, Law::Commutativity);
+
+        let laws = Laws {
+            identity: Some(0),
+            ..Laws::default()
+        };
+        let error = check_laws::<MaxComputation<i64>>(&[-3, 5], &laws).unwrap_err();
+        assert_eq!(error.law, Law::Identity);
+        assert_eq!(error.left, 0);
+        assert_eq!(error.right, -3);
+
+        let laws = Laws {
+            idempotent: true,
+            ..Laws::default()
+        };
+        let error = check_laws::<SumComputation<i64>>(&[0, 2], &laws).unwrap_err();
+        assert_eq!(error.law, Law::Idempotence);
+        assert_eq!(error.operands, vec![2]);
+
+        assert_eq!(
+            check_laws::<AccumulatingSum>(&[1, 2], &Laws::default()),
+            Ok(())
+        );
+        let laws = Laws {
+            replaces: true,
+            ..Laws::default()
+        };
+        let error = check_laws::<AccumulatingSum>(&[1, 2], &laws).unwrap_err();
+        assert_eq!(error.law, Law::UpdateConsistency);
+        assert_eq!(error.input, Some(1));
+
+        let error = check_update_consistency::<AverageComputation>(&[4], &[0]).unwrap_err();
+        assert_eq!(
+            error,
+            Counterexample {
+                law: Law::UpdateConsistency,
+                operands: vec![0],
+                input: Some(4),
+                left: 2,
+                right: 4,
+            }
+        );
+        assert_eq!(
+            error.to_string(),
+            "update consistency does not hold for [0] updated with 4: 2 != 4"
+        );
+    }
+
+    #[test]
+    fn test_associativity_cap() {
+        let samples: Vec<i64> = (0..20).collect();
+        let laws = Laws {
+            identity: Some(0),
+            commutative: true,
+            idempotent: false,
+            replaces: true,
+        };
+
+        assert_eq!(check_laws::<SumComputation<i64>>(&samples, &laws), Ok(()));
+    }
+}
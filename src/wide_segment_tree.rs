@@ -0,0 +1,227 @@
+use crate::{
+    computation::{append, prepend, MaxComputation, SegmentTreeComputation, SumComputation},
+    errors::{SegmentTreeError, SegmentTreeResult},
+};
+use alloc::{vec, vec::Vec};
+use core::marker::PhantomData;
+
+/// Segment tree where every node has `B` children, stored level by level.
+///
+/// Each level is a contiguous `Vec`, so a node's children are adjacent and
+/// queries touch `O(log_B n)` cache lines. Children are folded with plain
+/// slice loops, which the compiler vectorizes when `combine` is a primitive
+/// integer operation such as `+` or `max`, and runs as scalar code otherwise.
+pub struct WideSegmentTree<I, O, C, const B: usize>
+where
+    C: SegmentTreeComputation,
+{
+    levels: Vec<Vec<O>>,
+    phantom: PhantomData<(C, I)>,
+}
+
+pub type SumWideSegmentTree<T> = WideSegmentTree<T, T, SumComputation<T>, 16>;
+pub type MaxWideSegmentTree<T> = WideSegmentTree<T, T, MaxComputation<T>, 16>;
+
+impl<I, O, C, const B: usize> WideSegmentTree<I, O, C, B>
+where
+    O: Copy,
+    C: SegmentTreeComputation<Input = I, Output = O>,
+{
+    pub fn build(arr: &[I]) -> Self {
+        const { assert!(B >= 2, "A node needs at least two children") };
+
+        let mut levels: Vec<Vec<O>> = vec![arr.iter().map(C::init).collect()];
+        while let Some(level) = levels.last().filter(|level| level.len() > 1) {
+            let parents = level.chunks(B).map(fold::<C>).collect();
+            levels.push(parents);
+        }
+
+        Self {
+            levels,
+            phantom: PhantomData,
+        }
+    }
+
+    pub fn get(&self, mut left: usize, mut right: usize) -> SegmentTreeResult<O> {
+        if left > right {
+            return Err(SegmentTreeError::InvalidRange { left, right });
+        } else if right >= self.len() {
+            return Err(SegmentTreeError::OutOfBounds {
+                index: right,
+                len: self.len(),
+            });
+        }
+
+        // Partial blocks are peeled off both ends while climbing, keeping the
+        // left and right parts apart so non-commutative computations work.
+        let mut left_result: Option<O> = None;
+        let mut right_result: Option<O> = None;
+
+        for level in &self.levels {
+            if left / B == right / B {
+                let middle = append::<C>(left_result, fold::<C>(&level[left..=right]));
+                return Ok(prepend::<C>(middle, right_result));
+            }
+
+            let block_end = (left / B + 1) * B;
+            if left != block_end - B {
+                left_result = Some(append::<C>(left_result, fold::<C>(&level[left..block_end])));
+                left = block_end;
+            }
+
+            let block_start = right / B * B;
+            if right + 1 != (block_start + B).min(level.len()) {
+                right_result = Some(prepend::<C>(
+                    fold::<C>(&level[block_start..=right]),
+                    right_result,
+                ));
+                right = block_start - 1;
+            }
+
+            if left > right {
+                break;
+            }
+
+            left /= B;
+            right /= B;
+        }
+
+        match (left_result, right_result) {
+            (Some(left_result), Some(right_result)) => Ok(C::combine(&left_result, &right_result)),
+            (result, None) | (None, result) => {
+                Ok(result.expect("A non-empty range leaves a partial result"))
+            }
+        }
+    }
+
+    pub fn modify(&mut self, mut pos: usize, value: &I) -> SegmentTreeResult<()> {
+        if pos >= self.len() {
+            return Err(SegmentTreeError::OutOfBounds {
+                index: pos,
+                len: self.len(),
+            });
+        }
+
+        self.levels[0][pos] = C::update(&self.levels[0][pos], value);
+
+        for level in 1..self.levels.len() {
+            let (children, parents) = self.levels.split_at_mut(level);
+            let children = &children[level - 1];
+
+            pos /= B;
+            let start = pos * B;
+            let end = (start + B).min(children.len());
+            parents[0][pos] = fold::<C>(&children[start..end]);
+        }
+
+        Ok(())
+    }
+
+    pub fn len(&self) -> usize {
+        self.levels[0].len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+}
+
+#[inline]
+fn fold<C>(values: &[C::Output]) -> C::Output
+where
+    C: SegmentTreeComputation,
+    C::Output: Copy,
+{
+    values[1..]
+        .iter()
+        .fold(values[0], |acc, cur| C::combine(&acc, cur))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{MaxWideSegmentTree, SumWideSegmentTree, WideSegmentTree};
+    use crate::{
+        computation::{RollingHash, RollingHashComputation, SumComputation},
+        SegmentTreeError,
+    };
+
+    #[test]
+    fn test_build() {
+        let arr: Vec<i64> = (1..=10).collect();
+        let tree = WideSegmentTree::<_, _, SumComputation<i64>, 4>::build(&arr);
+
+        assert_eq!(tree.len(), arr.len());
+        assert_eq!(tree.levels.len(), 3);
+        assert_eq!(tree.levels[1], vec![10, 26, 19]);
+        assert_eq!(tree.levels[2], vec![55]);
+    }
+
+    #[test]
+    fn test_get_and_modify() {
+        let mut arr: Vec<i64> = (0..75).map(|value| (value * 37 % 23) - 11).collect();
+        let mut sum = SumWideSegmentTree::build(&arr);
+        let mut max = MaxWideSegmentTree::build(&arr);
+        let mut narrow = WideSegmentTree::<_, _, SumComputation<i64>, 2>::build(&arr);
+
+        for (pos, value) in [(0, 100), (74, -50), (31, 7), (16, 0)] {
+            arr[pos] = value;
+            assert_eq!(sum.modify(pos, &value), Ok(()));
+            assert_eq!(max.modify(pos, &value), Ok(()));
+            assert_eq!(narrow.modify(pos, &value), Ok(()));
+
+            for left in 0..arr.len() {
+                for right in left..arr.len() {
+                    let slice = &arr[left..=right];
+
+                    assert_eq!(sum.get(left, right), Ok(slice.iter().sum()));
+                    assert_eq!(narrow.get(left, right), Ok(slice.iter().sum()));
+                    assert_eq!(max.get(left, right), Ok(*slice.iter().max().unwrap()));
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_non_commutative_order() {
+        let text = b"the quick brown fox jumps over the lazy dog";
+        let tree = WideSegmentTree::<_, _, RollingHashComputation<u8>, 3>::build(text);
+
+        for left in 0..text.len() {
+            for right in left..text.len() {
+                let expected = RollingHash::from_slice(&text[left..=right]);
+
+                assert_eq!(tree.get(left, right), Ok(expected));
+            }
+        }
+    }
+
+    #[test]
+    fn test_errors() {
+        let mut tree = SumWideSegmentTree::build(&[1, 2, 3]);
+        let (left, right) = (2, 1);
+
+        assert_eq!(
+            tree.get(left, right),
+            Err(SegmentTreeError::InvalidRange { left, right })
+        );
+        assert_eq!(
+            tree.get(0, 3),
+            Err(SegmentTreeError::OutOfBounds { index: 3, len: 3 })
+        );
+        assert_eq!(
+            tree.modify(3, &1),
+            Err(SegmentTreeError::OutOfBounds { index: 3, len: 3 })
+        );
+
+        let mut empty = SumWideSegmentTree::<i32>::build(&[]);
+        assert!(empty.is_empty());
+        assert_eq!(
+            empty.get(0, 0),
+            Err(SegmentTreeError::OutOfBounds { index: 0, len: 0 })
+        );
+        assert_eq!(
+            empty.modify(0, &1),
+            Err(SegmentTreeError::OutOfBounds { index: 0, len: 0 })
+        );
+    }
+}